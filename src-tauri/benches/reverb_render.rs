@@ -0,0 +1,69 @@
+//! Full-song render time of the convolution reverb.
+//! Run with `cargo bench --bench reverb_render`.
+
+#[path = "../src/convolution.rs"]
+mod convolution;
+
+use std::time::Instant;
+
+use convolution::PartitionedConvolver;
+
+const SAMPLE_RATE: usize = 44100;
+const SONG_SECONDS: usize = 240;
+const DIRECT_SECONDS: usize = 2;
+
+fn main() {
+    // Deterministic noise for a four-minute stereo song and a 2 s decaying impulse
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut noise = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state >> 40) as f32 / (1u64 << 24) as f32 * 2.0 - 1.0
+    };
+
+    let song: Vec<Vec<f32>> = (0..2)
+        .map(|_| (0..SAMPLE_RATE * SONG_SECONDS).map(|_| noise() * 0.5).collect())
+        .collect();
+    let impulse_len = SAMPLE_RATE * 2;
+    let impulse: Vec<f32> = (0..impulse_len)
+        .map(|i| noise() * (-3.0 * i as f32 / impulse_len as f32).exp())
+        .collect();
+
+    let started = Instant::now();
+    let convolver = PartitionedConvolver::new(&impulse).expect("convolver");
+    for channel in &song {
+        std::hint::black_box(convolver.process(channel, channel.len()).expect("convolution"));
+    }
+    let partitioned = started.elapsed();
+    println!(
+        "partitioned FFT: {} s stereo song in {:.2} s",
+        SONG_SECONDS,
+        partitioned.as_secs_f64()
+    );
+
+    // The direct method on a short excerpt, extrapolated to the full song
+    let excerpt = &song[0][..SAMPLE_RATE * DIRECT_SECONDS];
+    let started = Instant::now();
+    let mut direct = vec![0.0f32; excerpt.len()];
+    for (i, &sample) in excerpt.iter().enumerate() {
+        for (out, &h) in direct[i..].iter_mut().zip(&impulse) {
+            *out += sample * h;
+        }
+    }
+    let per_second = started.elapsed().as_secs_f64() / DIRECT_SECONDS as f64;
+    println!(
+        "direct: {:.2} s per second of mono audio, ~{:.0} s for the stereo song",
+        per_second,
+        per_second * (2 * SONG_SECONDS) as f64
+    );
+
+    let fast = convolver.process(excerpt, excerpt.len()).expect("convolution");
+    let max_error = fast
+        .iter()
+        .zip(&direct)
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0f32, f32::max);
+    let peak = direct.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    println!("max difference to direct convolution: {:e} of peak", max_error / peak);
+}
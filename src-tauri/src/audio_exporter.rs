@@ -0,0 +1,185 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::audio_loader::AudioLoader;
+use crate::audio_processor::AudioProcessor;
+use crate::audio_types::{
+    AdvancedAudioEffects, AudioBuffer, AudioMarker, ExportFormat, ExportTarget, ProcessingRecipe,
+};
+use crate::limiter::{self, LimiterSettings};
+use crate::loudness;
+use crate::recipe;
+use crate::resampler;
+
+/// Ceiling applied after loudness normalization, in dBTP
+const NORMALIZE_CEILING_DB: f32 = -1.0;
+const WAV_BIT_DEPTHS: [u16; 3] = [16, 24, 32];
+const MP3_BITRATES: [u32; 6] = [128, 160, 192, 224, 256, 320];
+/// Sample rates the LAME encoder accepts
+const MP3_SAMPLE_RATES: [u32; 9] = [8000, 11025, 12000, 16000, 22050, 24000, 32000, 44100, 48000];
+
+pub struct AudioExporter;
+
+impl AudioExporter {
+    /// Process the audio once and write the result to every export target.
+    /// Returns the written paths in target order.
+    pub fn export(
+        audio_buffer: AudioBuffer,
+        effects: &AdvancedAudioEffects,
+        targets: &[ExportTarget],
+        markers: &[AudioMarker],
+        source_path: Option<&str>,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        if targets.is_empty() {
+            return Err("No export targets given".into());
+        }
+
+        let name = source_path
+            .and_then(|path| Path::new(path).file_stem())
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("processed-audio");
+
+        // Resolve every path up front so a bad job fails before any rendering
+        let paths: Vec<String> = targets
+            .iter()
+            .map(|target| Self::expand_path_template(target, name, audio_buffer.sample_rate))
+            .collect();
+        let mut unique = HashSet::new();
+        if let Some(duplicate) = paths.iter().find(|path| !unique.insert(path.as_str())) {
+            return Err(format!("Several export targets write to {}", duplicate).into());
+        }
+        for target in targets {
+            Self::validate_target(target, audio_buffer.sample_rate, audio_buffer.channels.len())?;
+        }
+
+        let processing_recipe = recipe::build_recipe(effects, source_path)?;
+        let source_len = audio_buffer.channels.first().map_or(0, |c| c.len());
+        let processed = AudioProcessor::process_audio(audio_buffer, effects)?;
+
+        // Tempo and nightcore change the length; markers move with the audio
+        let processed_len = processed.channels.first().map_or(0, |c| c.len());
+        let timeline_ratio = if source_len > 0 {
+            processed_len as f64 / source_len as f64
+        } else {
+            1.0
+        };
+
+        for (target, path) in targets.iter().zip(&paths) {
+            Self::write_target(&processed, target, path, markers, timeline_ratio, &processing_recipe)?;
+        }
+
+        Ok(paths)
+    }
+
+    /// Reject settings the writers would refuse, before anything is rendered or written
+    fn validate_target(
+        target: &ExportTarget,
+        source_rate: u32,
+        channel_count: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let sample_rate = target.sample_rate.unwrap_or(source_rate);
+        if sample_rate == 0 {
+            return Err("Export sample rate must be above zero".into());
+        }
+
+        match target.format {
+            ExportFormat::Wav => {
+                let bit_depth = target.bit_depth.unwrap_or(32);
+                if !WAV_BIT_DEPTHS.contains(&bit_depth) {
+                    return Err(format!("Unsupported WAV bit depth: {}", bit_depth).into());
+                }
+            }
+            ExportFormat::Mp3 => {
+                if !cfg!(feature = "mp3") {
+                    return Err("MP3 export is not available: build with the `mp3` feature".into());
+                }
+                let bitrate = target.bitrate.unwrap_or(320);
+                if !MP3_BITRATES.contains(&bitrate) {
+                    return Err(format!("Unsupported MP3 bitrate: {} kbps", bitrate).into());
+                }
+                if !MP3_SAMPLE_RATES.contains(&sample_rate) {
+                    return Err(format!("Unsupported MP3 sample rate: {} Hz", sample_rate).into());
+                }
+                if channel_count == 0 || channel_count > 2 {
+                    return Err("MP3 export supports mono and stereo audio only".into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Convert the processed render to one target's settings and write it
+    fn write_target(
+        processed: &AudioBuffer,
+        target: &ExportTarget,
+        path: &str,
+        markers: &[AudioMarker],
+        timeline_ratio: f64,
+        processing_recipe: &ProcessingRecipe,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut rendered = match target.sample_rate {
+            Some(rate) => resampler::resample_buffer(processed, rate)?,
+            None => processed.clone(),
+        };
+
+        if let Some(target_lufs) = target.loudness_target {
+            loudness::normalize_loudness(&mut rendered, target_lufs);
+            // Make-up gain can push peaks past full scale; catch them before the writer clips
+            limiter::apply_limiter(
+                &mut rendered.channels,
+                rendered.sample_rate,
+                LimiterSettings {
+                    threshold_db: NORMALIZE_CEILING_DB,
+                    lookahead_ms: 5.0,
+                    release: 0.1,
+                },
+            );
+        }
+
+        match target.format {
+            ExportFormat::Wav => {
+                // Marker positions are source frames: follow the processing timeline and the sample rate
+                let ratio = timeline_ratio * rendered.sample_rate as f64 / processed.sample_rate as f64;
+                let scale = |frames: u32| (frames as f64 * ratio).round() as u32;
+                let markers: Vec<AudioMarker> = markers
+                    .iter()
+                    .map(|marker| AudioMarker {
+                        position: scale(marker.position),
+                        length: marker.length.map(scale),
+                        ..marker.clone()
+                    })
+                    .collect();
+
+                AudioLoader::save_as_wav(
+                    &rendered,
+                    path,
+                    target.bit_depth.unwrap_or(32),
+                    &markers,
+                    Some(processing_recipe),
+                )
+            }
+            ExportFormat::Mp3 => AudioLoader::save_as_mp3(
+                &rendered,
+                path,
+                target.bitrate.unwrap_or(320),
+                Some(processing_recipe),
+            ),
+        }
+    }
+
+    fn expand_path_template(target: &ExportTarget, name: &str, processed_rate: u32) -> String {
+        let extension = match target.format {
+            ExportFormat::Wav => "wav",
+            ExportFormat::Mp3 => "mp3",
+        };
+
+        target
+            .path_template
+            .replace("{name}", name)
+            .replace("{ext}", extension)
+            .replace("{sample_rate}", &target.sample_rate.unwrap_or(processed_rate).to_string())
+            .replace("{bit_depth}", &target.bit_depth.unwrap_or(32).to_string())
+            .replace("{bitrate}", &target.bitrate.unwrap_or(320).to_string())
+    }
+}
@@ -10,7 +10,9 @@ use symphonia::core::probe::Hint;
 use std::fs::File;
 use std::io::BufReader;
 
-use crate::audio_types::AudioBuffer;
+use crate::audio_types::{AudioBuffer, AudioMarker, LoadedAudio, ProcessingRecipe};
+use crate::recipe;
+use crate::wav_chunks;
 
 pub struct AudioLoader;
 
@@ -30,6 +32,29 @@ impl AudioLoader {
         }
     }
 
+    /// Load an audio file along with any markers it carries
+    pub fn open(file_path: &str) -> Result<LoadedAudio, Box<dyn std::error::Error>> {
+        let buffer = Self::load_audio_file(file_path)?;
+        let markers = Self::load_markers(file_path)?;
+        Ok(LoadedAudio { buffer, markers })
+    }
+
+    /// Read cue, region and loop markers stored in a WAV file
+    pub fn load_markers(file_path: &str) -> Result<Vec<AudioMarker>, Box<dyn std::error::Error>> {
+        let is_wav = Path::new(file_path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("wav"))
+            .unwrap_or(false);
+
+        if !is_wav {
+            return Ok(vec![]);
+        }
+
+        let chunks = wav_chunks::read_chunks(file_path)?;
+        Ok(wav_chunks::decode_markers(&chunks))
+    }
+
     /// Load WAV files directly using hound
     fn load_wav(file_path: &str) -> Result<AudioBuffer, Box<dyn std::error::Error>> {
         let mut reader = WavReader::open(file_path)?;
@@ -40,8 +65,10 @@ impl AudioLoader {
                 reader.samples::<f32>().collect::<Result<Vec<_>, _>>()?
             }
             hound::SampleFormat::Int => {
+                // hound returns integer samples at their native bit depth
+                let full_scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
                 let samples: Vec<i32> = reader.samples::<i32>().collect::<Result<Vec<_>, _>>()?;
-                samples.into_iter().map(|s| s as f32 / i32::MAX as f32).collect()
+                samples.into_iter().map(|s| s as f32 / full_scale).collect()
             }
         };
 
@@ -254,31 +281,131 @@ impl AudioLoader {
         channels
     }
 
-    /// Save audio buffer as WAV file
+    /// Save audio buffer as WAV file, writing markers to cue/adtl/smpl chunks
+    /// and the processing recipe to a custom chunk.
+    /// `bit_depth` is 16 or 24 for integer PCM, 32 for float.
     pub fn save_as_wav(
         audio_buffer: &AudioBuffer,
         output_path: &str,
+        bit_depth: u16,
+        markers: &[AudioMarker],
+        processing_recipe: Option<&ProcessingRecipe>,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        let sample_format = match bit_depth {
+            16 | 24 => hound::SampleFormat::Int,
+            32 => hound::SampleFormat::Float,
+            _ => return Err(format!("Unsupported WAV bit depth: {}", bit_depth).into()),
+        };
+
         let spec = WavSpec {
             channels: audio_buffer.channels.len() as u16,
             sample_rate: audio_buffer.sample_rate,
-            bits_per_sample: 32,
-            sample_format: hound::SampleFormat::Float,
+            bits_per_sample: bit_depth,
+            sample_format,
         };
 
         let mut writer = WavWriter::create(output_path, spec)?;
+        let int_scale = ((1i64 << (bit_depth - 1)) - 1) as f32;
 
         // Interleave channels
         let frame_count = audio_buffer.channels[0].len();
         for frame in 0..frame_count {
             for channel in &audio_buffer.channels {
                 if frame < channel.len() {
-                    writer.write_sample(channel[frame])?;
+                    match sample_format {
+                        hound::SampleFormat::Float => writer.write_sample(channel[frame])?,
+                        hound::SampleFormat::Int => {
+                            let sample = (channel[frame].clamp(-1.0, 1.0) * int_scale).round() as i32;
+                            writer.write_sample(sample)?
+                        }
+                    }
                 }
             }
         }
 
         writer.finalize()?;
+
+        let mut chunks = wav_chunks::encode_markers(markers, audio_buffer.sample_rate);
+        if let Some(processing_recipe) = processing_recipe {
+            chunks.push(recipe::encode_chunk(processing_recipe)?);
+        }
+        wav_chunks::append_chunks(output_path, &chunks)?;
+        Ok(())
+    }
+
+    /// Save audio buffer as MP3, storing the processing recipe in the ID3 comment
+    #[cfg(feature = "mp3")]
+    pub fn save_as_mp3(
+        audio_buffer: &AudioBuffer,
+        output_path: &str,
+        bitrate: u32,
+        processing_recipe: Option<&ProcessingRecipe>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        use mp3lame_encoder::{Bitrate, Builder, FlushNoGap, InterleavedPcm, MonoPcm, Quality};
+
+        let bitrate = match bitrate {
+            128 => Bitrate::Kbps128,
+            160 => Bitrate::Kbps160,
+            192 => Bitrate::Kbps192,
+            224 => Bitrate::Kbps224,
+            256 => Bitrate::Kbps256,
+            320 => Bitrate::Kbps320,
+            _ => return Err(format!("Unsupported MP3 bitrate: {} kbps", bitrate).into()),
+        };
+
+        let channel_count = audio_buffer.channels.len();
+        if channel_count == 0 || channel_count > 2 {
+            return Err("MP3 export supports mono and stereo audio only".into());
+        }
+
+        let mut builder = Builder::new().ok_or("Failed to create MP3 encoder")?;
+        builder.set_num_channels(channel_count as u8).map_err(|e| format!("{:?}", e))?;
+        builder.set_sample_rate(audio_buffer.sample_rate).map_err(|e| format!("{:?}", e))?;
+        builder.set_brate(bitrate).map_err(|e| format!("{:?}", e))?;
+        builder.set_quality(Quality::Best).map_err(|e| format!("{:?}", e))?;
+
+        let mut encoder = builder.build().map_err(|e| format!("{:?}", e))?;
+
+        // Interleave channels as 16-bit PCM
+        let frame_count = audio_buffer.channels[0].len();
+        let mut pcm = Vec::with_capacity(frame_count * channel_count);
+        for frame in 0..frame_count {
+            for channel in &audio_buffer.channels {
+                let sample = channel.get(frame).copied().unwrap_or(0.0);
+                pcm.push((sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16);
+            }
+        }
+
+        let mut mp3_data = Vec::with_capacity(mp3lame_encoder::max_required_buffer_size(frame_count));
+        if channel_count == 2 {
+            encoder.encode_to_vec(InterleavedPcm(&pcm), &mut mp3_data)
+        } else {
+            encoder.encode_to_vec(MonoPcm(&pcm), &mut mp3_data)
+        }
+        .map_err(|e| format!("{:?}", e))?;
+        encoder
+            .flush_to_vec::<FlushNoGap>(&mut mp3_data)
+            .map_err(|e| format!("{:?}", e))?;
+
+        // The recipe goes in an ID3v2 tag ahead of the audio frames
+        let mut file_data = match processing_recipe {
+            Some(processing_recipe) => recipe::encode_id3_tag(processing_recipe)?,
+            None => Vec::new(),
+        };
+        file_data.extend_from_slice(&mp3_data);
+
+        std::fs::write(output_path, file_data)?;
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// MP3 encoding needs the LAME encoder, which is only linked with the `mp3` feature
+    #[cfg(not(feature = "mp3"))]
+    pub fn save_as_mp3(
+        _audio_buffer: &AudioBuffer,
+        _output_path: &str,
+        _bitrate: u32,
+        _processing_recipe: Option<&ProcessingRecipe>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Err("MP3 export is not available: build with the `mp3` feature".into())
+    }
+}
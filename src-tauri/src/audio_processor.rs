@@ -2,40 +2,244 @@ use std::f32::consts::PI;
 use realfft::RealFftPlanner;
 use rustfft::{FftPlanner, num_complex::Complex32};
 use apodize;
-
-use crate::audio_types::{AudioBuffer, AdvancedAudioEffects};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::audio_loader::AudioLoader;
+use crate::audio_types::{
+    AudioBuffer, AdvancedAudioEffects, BassBoostMode, DetectionMode, GainReductionTrace, ProcessingReport,
+    NoteValue, PanLaw, ReverbType, SaturationCurve, TempoMode,
+};
+use crate::biquad::{Biquad, BiquadCoefficients};
+use crate::compressor::{self, CompressorSettings};
+use crate::convolution::PartitionedConvolver;
+use crate::delay::{self, DelaySettings};
+use crate::equalizer;
+use crate::freeverb::{Freeverb, FreeverbSettings};
+use crate::gate::{self, GateSettings};
+use crate::limiter::{self, LimiterSettings};
+use crate::lofi::{self, LofiSettings};
+use crate::modulation::{self, ModulationSettings};
+use crate::multiband;
+use crate::phase_vocoder::PhaseVocoder;
+use crate::resampler;
+use crate::saturation::{self, BitcrusherSettings, SaturationSettings};
+use crate::stereo::{self, StereoSettings};
+use crate::vocal_separation::VocalSeparator;
+
+/// Bandwidth of the optional resonant bump on the bass shelf
+const BASS_RESONANCE_Q: f64 = 1.4;
+/// Fade applied where a loaded impulse response is cut short (ms)
+const IR_FADE_MS: f32 = 10.0;
 
 pub struct AudioProcessor;
 
 impl AudioProcessor {
     /// Apply all effects to the audio buffer
     pub fn process_audio(
-        mut audio_buffer: AudioBuffer,
+        audio_buffer: AudioBuffer,
         effects: &AdvancedAudioEffects,
     ) -> Result<AudioBuffer, Box<dyn std::error::Error>> {
+        Self::process_audio_with_report(audio_buffer, effects).map(|(audio_buffer, _)| audio_buffer)
+    }
+
+    /// Apply all effects and return what the stages measured along the way
+    pub fn process_audio_with_report(
+        mut audio_buffer: AudioBuffer,
+        effects: &AdvancedAudioEffects,
+    ) -> Result<(AudioBuffer, ProcessingReport), Box<dyn std::error::Error>> {
+        let mut report = ProcessingReport::default();
+
+        // Apply vocal extraction / instrumental separation on the source mix
+        if effects.vocal_extractor.unwrap_or(false) {
+            Self::apply_vocal_separation(
+                &mut audio_buffer,
+                effects.vocal_sensitivity.unwrap_or(0.5),
+                effects.instrumental_separation.unwrap_or(0.0),
+            )?;
+        }
+
         // Apply volume
         Self::apply_volume(&mut audio_buffer, effects.volume);
 
         // Apply tempo change
         if (effects.tempo - 1.0).abs() > 0.001 {
-            audio_buffer = Self::apply_tempo_change(audio_buffer, effects.tempo)?;
+            audio_buffer = Self::apply_tempo_change(
+                audio_buffer,
+                effects.tempo,
+                effects.tempo_mode.unwrap_or(TempoMode::Varispeed),
+                effects.pitch_transient_handling.unwrap_or(true),
+            )?;
+        }
+
+        // Apply nightcore/daycore
+        let nightcore_ratio = match effects.nightcore_semitones {
+            Some(semitones) => 2.0_f32.powf(semitones / 12.0),
+            None => effects.nightcore.unwrap_or(1.0),
+        };
+        if (nightcore_ratio - 1.0).abs() > 0.001 {
+            audio_buffer = Self::apply_nightcore(audio_buffer, nightcore_ratio)?;
+        }
+
+        // Apply pitch shift
+        let pitch_semitones = effects.pitch_shift.unwrap_or(0.0) + effects.pitch_shift_cents.unwrap_or(0.0) / 100.0;
+        if pitch_semitones.abs() > 0.001 {
+            Self::apply_pitch_shift(&mut audio_buffer, pitch_semitones, effects.pitch_transient_handling.unwrap_or(true))?;
+        }
+
+        // Apply formant-preserving pitch (Pitcher)
+        let pitcher_semitones = effects.pitcher.unwrap_or(0.0);
+        let formant_semitones = effects.formant_shift.unwrap_or(0.0);
+        if pitcher_semitones.abs() > 0.001 || formant_semitones.abs() > 0.001 {
+            Self::apply_pitcher(&mut audio_buffer, pitcher_semitones, formant_semitones, effects.pitch_transient_handling.unwrap_or(true))?;
         }
 
         // Apply bass boost
         if effects.bass_boost > 0.001 {
-            Self::apply_bass_boost(&mut audio_buffer, effects.bass_boost)?;
+            Self::apply_bass_boost(&mut audio_buffer, effects);
         }
 
         // Apply EQ if any EQ settings are present
-        if effects.eq_low.is_some() || effects.eq_low_mid.is_some() || 
-           effects.eq_mid.is_some() || effects.eq_high_mid.is_some() || 
-           effects.eq_high.is_some() {
-            Self::apply_equalizer(&mut audio_buffer, effects)?;
+        let eq_bands = equalizer::bands_from_effects(effects);
+        if !eq_bands.is_empty() {
+            let sample_rate = audio_buffer.sample_rate;
+            stereo::with_mid_side(&mut audio_buffer.channels, effects.eq_mid_side, |channels| {
+                if effects.eq_linear_phase.unwrap_or(false) {
+                    let latency = equalizer::apply_linear_phase(channels, &eq_bands, sample_rate)?;
+                    report.eq_latency_samples = Some(latency as u32);
+                } else {
+                    equalizer::apply_bands(channels, &eq_bands, sample_rate);
+                }
+                Ok::<(), Box<dyn std::error::Error>>(())
+            })?;
+        }
+
+        // Apply noise gate if enabled
+        if effects.gate.unwrap_or(false) && effects.audio_processing_enabled.unwrap_or(true) {
+            Self::apply_gate(&mut audio_buffer, effects);
+        }
+
+        // Apply saturation if enabled
+        if effects.saturation.unwrap_or(false) && effects.audio_processing_enabled.unwrap_or(true) {
+            let settings = SaturationSettings {
+                curve: effects.saturation_curve.unwrap_or(SaturationCurve::Tape),
+                drive_db: effects.saturation_drive.unwrap_or(6.0),
+                output_db: effects.saturation_output.unwrap_or(0.0),
+                mix: effects.saturation_mix.unwrap_or(1.0),
+                oversampling: effects.saturation_oversampling.unwrap_or(4) as usize,
+            };
+            saturation::apply_saturation(&mut audio_buffer.channels, audio_buffer.sample_rate, settings)?;
+        }
+
+        // Apply bitcrusher if enabled
+        if effects.bitcrusher.unwrap_or(false) && effects.audio_processing_enabled.unwrap_or(true) {
+            let settings = BitcrusherSettings {
+                bits: effects.bitcrusher_bits.unwrap_or(8),
+                sample_rate_hz: effects.bitcrusher_sample_rate,
+                mix: effects.bitcrusher_mix.unwrap_or(1.0),
+                oversampling: effects.saturation_oversampling.unwrap_or(4) as usize,
+            };
+            saturation::apply_bitcrusher(&mut audio_buffer.channels, audio_buffer.sample_rate, settings)?;
+        }
+
+        // Apply compressor if enabled
+        if effects.compressor.unwrap_or(false) && effects.audio_processing_enabled.unwrap_or(true) {
+            report.compressor_gain_reduction = Some(Self::apply_compressor(&mut audio_buffer, effects));
+        }
+
+        // Apply multiband compressor if enabled
+        if effects.multiband_compressor.unwrap_or(false) && effects.audio_processing_enabled.unwrap_or(true) {
+            report.multiband_gain_reduction = Some(Self::apply_multiband_compressor(&mut audio_buffer, effects)?);
+        }
+
+        // Apply chorus if enabled
+        if effects.chorus.unwrap_or(false) && effects.audio_processing_enabled.unwrap_or(true) {
+            let settings = ModulationSettings {
+                rate_hz: Self::lfo_rate(effects, effects.chorus_rate, effects.chorus_sync, 0.8),
+                depth: effects.chorus_depth.unwrap_or(0.5),
+                feedback: effects.chorus_feedback.unwrap_or(0.0),
+                mix: effects.chorus_mix.unwrap_or(0.5),
+                stereo_phase_deg: effects.chorus_stereo_phase.unwrap_or(90.0),
+            };
+            modulation::apply_chorus(&mut audio_buffer.channels, audio_buffer.sample_rate, settings);
+        }
+
+        // Apply flanger if enabled
+        if effects.flanger.unwrap_or(false) && effects.audio_processing_enabled.unwrap_or(true) {
+            let settings = ModulationSettings {
+                rate_hz: Self::lfo_rate(effects, effects.flanger_rate, effects.flanger_sync, 0.25),
+                depth: effects.flanger_depth.unwrap_or(0.7),
+                feedback: effects.flanger_feedback.unwrap_or(0.5),
+                mix: effects.flanger_mix.unwrap_or(0.5),
+                stereo_phase_deg: effects.flanger_stereo_phase.unwrap_or(90.0),
+            };
+            modulation::apply_flanger(&mut audio_buffer.channels, audio_buffer.sample_rate, settings);
+        }
+
+        // Apply phaser if enabled
+        if effects.phaser.unwrap_or(false) && effects.audio_processing_enabled.unwrap_or(true) {
+            let settings = ModulationSettings {
+                rate_hz: Self::lfo_rate(effects, effects.phaser_rate, effects.phaser_sync, 0.5),
+                depth: effects.phaser_depth.unwrap_or(0.8),
+                feedback: effects.phaser_feedback.unwrap_or(0.4),
+                mix: effects.phaser_mix.unwrap_or(0.5),
+                stereo_phase_deg: effects.phaser_stereo_phase.unwrap_or(90.0),
+            };
+            modulation::apply_phaser(&mut audio_buffer.channels, audio_buffer.sample_rate, settings);
+        }
+
+        // Apply lo-fi vinyl/tape simulation if enabled
+        if effects.lofi.unwrap_or(false) && effects.audio_processing_enabled.unwrap_or(true) {
+            let settings = LofiSettings {
+                seed: effects.lofi_seed.unwrap_or(0) as u64,
+                crackle_density: effects.lofi_crackle_density.unwrap_or(8.0),
+                crackle_level_db: effects.lofi_crackle_level.unwrap_or(-30.0),
+                hiss_level_db: effects.lofi_hiss,
+                wow_rate_hz: effects.lofi_wow_rate.unwrap_or(0.5),
+                wow_depth_cents: effects.lofi_wow_depth.unwrap_or(8.0),
+                flutter_rate_hz: effects.lofi_flutter_rate.unwrap_or(6.0),
+                flutter_depth_cents: effects.lofi_flutter_depth.unwrap_or(3.0),
+                tape_drive_db: effects.lofi_tape_drive.unwrap_or(3.0),
+                low_cut_hz: effects.lofi_low_cut.unwrap_or(80.0),
+                high_cut_hz: effects.lofi_high_cut.unwrap_or(8000.0),
+            };
+            lofi::apply_lofi(&mut audio_buffer.channels, audio_buffer.sample_rate, settings)?;
+        }
+
+        // Apply delay if enabled
+        if effects.delay.unwrap_or(false) && effects.audio_processing_enabled.unwrap_or(true) {
+            let time_ms = match (effects.delay_sync, effects.bpm) {
+                (Some(note), Some(bpm)) => note.seconds(bpm) * 1000.0,
+                _ => effects.delay_time.unwrap_or(375.0),
+            };
+            let settings = DelaySettings {
+                time_ms,
+                feedback: effects.delay_feedback.unwrap_or(0.4),
+                mix: effects.delay_mix.unwrap_or(0.3),
+                ping_pong: effects.delay_ping_pong.unwrap_or(false),
+                low_cut_hz: effects.delay_low_cut,
+                high_cut_hz: effects.delay_high_cut,
+                ducking_db: effects.delay_ducking.unwrap_or(0.0),
+                duck_threshold_db: effects.delay_duck_threshold.unwrap_or(-30.0),
+            };
+            delay::apply_delay(&mut audio_buffer.channels, audio_buffer.sample_rate, settings);
+        }
+
+        // Apply stereo image controls if enabled
+        if effects.stereo.unwrap_or(false) && effects.audio_processing_enabled.unwrap_or(true) {
+            let settings = StereoSettings {
+                width: effects.stereo_width.unwrap_or(100.0) / 100.0,
+                mid_gain_db: effects.stereo_mid_gain.unwrap_or(0.0),
+                side_gain_db: effects.stereo_side_gain.unwrap_or(0.0),
+                balance: effects.stereo_balance.unwrap_or(0.0),
+                pan_law: effects.stereo_pan_law.unwrap_or(PanLaw::Balance),
+            };
+            stereo::apply_stereo(&mut audio_buffer.channels, settings);
         }
 
         // Apply limiter if enabled
         if effects.limiter.unwrap_or(false) && effects.audio_processing_enabled.unwrap_or(true) {
-            Self::apply_limiter(&mut audio_buffer, effects)?;
+            report.limiter_gain_reduction_db = Some(Self::apply_limiter(&mut audio_buffer, effects));
         }
 
         // Apply attenuator if enabled
@@ -44,11 +248,30 @@ impl AudioProcessor {
         }
 
         // Apply reverb
-        if effects.reverb > 0.001 {
-            audio_buffer = Self::apply_reverb(audio_buffer, effects.reverb)?;
+        if effects.reverb_wet.unwrap_or(effects.reverb) > 0.001 {
+            let reverb_type = effects.reverb_type.unwrap_or(ReverbType::Convolution);
+            // The convolution engine has always scaled its tail by the amount twice, so keep reverb²
+            let default_wet = match reverb_type {
+                ReverbType::Convolution => effects.reverb * effects.reverb,
+                ReverbType::Algorithmic => effects.reverb,
+            };
+            let reverb_wet = effects.reverb_wet.unwrap_or(default_wet);
+            let reverb_dry = effects.reverb_dry.unwrap_or(1.0 - effects.reverb);
+            audio_buffer = match reverb_type {
+                ReverbType::Convolution => Self::apply_reverb(audio_buffer, effects, reverb_dry, reverb_wet)?,
+                ReverbType::Algorithmic => {
+                    let settings = FreeverbSettings {
+                        room_size: effects.reverb_room_size.unwrap_or(0.5),
+                        damping: effects.reverb_damping.unwrap_or(0.5),
+                        pre_delay_ms: effects.reverb_pre_delay.unwrap_or(0.0),
+                        width: effects.reverb_width.unwrap_or(1.0),
+                    };
+                    Self::apply_algorithmic_reverb(audio_buffer, settings, reverb_dry, reverb_wet)
+                }
+            };
         }
 
-        Ok(audio_buffer)
+        Ok((audio_buffer, report))
     }
 
     /// Apply volume change
@@ -60,34 +283,26 @@ impl AudioProcessor {
         }
     }
 
-    /// Apply tempo change using simple resampling
+    /// Apply tempo change, either as a band-limited varispeed resample or as a
+    /// pitch-preserving phase-vocoder time stretch
     fn apply_tempo_change(
         audio_buffer: AudioBuffer,
         tempo: f32,
+        mode: TempoMode,
+        transient_handling: bool,
     ) -> Result<AudioBuffer, Box<dyn std::error::Error>> {
-        let new_length = (audio_buffer.channels[0].len() as f32 / tempo) as usize;
-        let mut new_channels = Vec::new();
-
-        for channel in &audio_buffer.channels {
-            let mut new_channel = Vec::with_capacity(new_length);
-            
-            for i in 0..new_length {
-                let original_index = (i as f32 * tempo) as usize;
-                if original_index < channel.len() {
-                    // Linear interpolation for smoother result
-                    let next_index = (original_index + 1).min(channel.len() - 1);
-                    let fraction = (i as f32 * tempo) - original_index as f32;
-                    
-                    let sample = channel[original_index] * (1.0 - fraction) + 
-                                channel[next_index] * fraction;
-                    new_channel.push(sample);
-                } else {
-                    new_channel.push(0.0);
-                }
-            }
-            new_channels.push(new_channel);
+        if !(tempo.is_finite() && tempo > 0.0) {
+            return Err(format!("Invalid tempo: {}", tempo).into());
         }
 
+        let stretch = 1.0 / tempo as f64;
+        let new_channels = match mode {
+            TempoMode::Varispeed => resampler::resample_channels(&audio_buffer.channels, stretch)?,
+            TempoMode::TimeStretch => PhaseVocoder::new(audio_buffer.sample_rate, transient_handling)
+                .time_stretch(&audio_buffer.channels, stretch)?,
+        };
+        let new_length = new_channels.first().map_or(0, |c| c.len());
+
         Ok(AudioBuffer {
             channels: new_channels,
             sample_rate: audio_buffer.sample_rate,
@@ -95,149 +310,209 @@ impl AudioProcessor {
         })
     }
 
-    /// Apply bass boost using a simple low-shelf filter
-    fn apply_bass_boost(
+    /// Apply nightcore: speed and pitch change together by `ratio`, using a
+    /// band-limited sinc resample played back at the original sample rate
+    fn apply_nightcore(
+        audio_buffer: AudioBuffer,
+        ratio: f32,
+    ) -> Result<AudioBuffer, Box<dyn std::error::Error>> {
+        if !(ratio.is_finite() && ratio > 0.0) {
+            return Err(format!("Invalid nightcore ratio: {}", ratio).into());
+        }
+
+        let channels = resampler::resample_channels(&audio_buffer.channels, 1.0 / ratio as f64)?;
+        let new_length = channels.first().map_or(0, |c| c.len());
+
+        Ok(AudioBuffer {
+            channels,
+            sample_rate: audio_buffer.sample_rate,
+            duration: new_length as f32 / audio_buffer.sample_rate as f32,
+        })
+    }
+
+    /// Apply pitch shift that keeps the duration, using the phase vocoder
+    fn apply_pitch_shift(
         audio_buffer: &mut AudioBuffer,
-        boost: f32,
+        semitones: f32,
+        transient_handling: bool,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let sample_rate = audio_buffer.sample_rate as f32;
-        let cutoff_freq = 200.0; // Bass frequency cutoff
-        let gain_db = boost * 20.0; // Convert to dB
-        let gain_linear = 10.0_f32.powf(gain_db / 20.0);
+        let vocoder = PhaseVocoder::new(audio_buffer.sample_rate, transient_handling);
+        audio_buffer.channels = vocoder.pitch_shift(&audio_buffer.channels, semitones as f64, None)?;
+        Ok(())
+    }
 
-        // Simple one-pole low-pass filter coefficients
-        let omega = 2.0 * PI * cutoff_freq / sample_rate;
-        let alpha = omega / (1.0 + omega);
+    /// Split the center-panned vocal from the rest of the mix and blend the two:
+    /// `instrumental_mix` 0.0 renders the vocal only, 1.0 the instrumental only.
+    /// Works on a stereo pair; buffers with more than two channels are rejected.
+    fn apply_vocal_separation(
+        audio_buffer: &mut AudioBuffer,
+        sensitivity: f32,
+        instrumental_mix: f32,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match audio_buffer.channels.len() {
+            0 | 1 => return Ok(()), // Mono has no panning to separate on
+            2 => {}
+            channels => return Err(format!("Vocal separation needs stereo audio, got {} channels", channels).into()),
+        }
 
-        for channel in &mut audio_buffer.channels {
-            let mut y_prev = 0.0;
-            
-            for sample in channel.iter_mut() {
-                // Low-pass filter
-                y_prev = alpha * *sample + (1.0 - alpha) * y_prev;
-                
-                // Apply boost to low frequencies and mix with original
-                let boosted_bass = y_prev * gain_linear;
-                let high_freq = *sample - y_prev;
-                *sample = boosted_bass + high_freq;
-                
-                // Prevent clipping
-                *sample = sample.clamp(-1.0, 1.0);
+        let separator = VocalSeparator::new(audio_buffer.sample_rate, sensitivity);
+        let stems = separator.separate(&audio_buffer.channels[0], &audio_buffer.channels[1])?;
+
+        let instrumental_mix = instrumental_mix.clamp(0.0, 1.0);
+        let vocal_mix = 1.0 - instrumental_mix;
+        for (channel, instrumental) in audio_buffer.channels.iter_mut().zip(&stems.instrumental) {
+            for ((sample, &v), &i) in channel.iter_mut().zip(&stems.vocal).zip(instrumental) {
+                *sample = vocal_mix * v + instrumental_mix * i;
             }
         }
 
         Ok(())
     }
 
-    /// Apply 5-band equalizer
-    fn apply_equalizer(
+    /// Apply Pitcher: pitch shift that keeps the formants in place, plus an
+    /// independent formant shift, both in semitones
+    fn apply_pitcher(
         audio_buffer: &mut AudioBuffer,
-        effects: &AdvancedAudioEffects,
+        pitch_semitones: f32,
+        formant_semitones: f32,
+        transient_handling: bool,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let sample_rate = audio_buffer.sample_rate as f32;
-        
-        // EQ band frequencies
-        let bands = [
-            (60.0, effects.eq_low.unwrap_or(0.5)),      // Low
-            (250.0, effects.eq_low_mid.unwrap_or(0.5)), // Low-Mid
-            (1000.0, effects.eq_mid.unwrap_or(0.5)),    // Mid
-            (4000.0, effects.eq_high_mid.unwrap_or(0.5)), // High-Mid
-            (16000.0, effects.eq_high.unwrap_or(0.5)),  // High
-        ];
-
-        for channel in &mut audio_buffer.channels {
-            let mut filtered_channel = channel.clone();
+        let vocoder = PhaseVocoder::new(audio_buffer.sample_rate, transient_handling);
+        audio_buffer.channels = vocoder.pitch_shift(
+            &audio_buffer.channels,
+            pitch_semitones as f64,
+            Some(formant_semitones as f64),
+        )?;
+        Ok(())
+    }
 
-            for (freq, gain_normalized) in &bands {
-                // Convert normalized gain (0-1) to dB (-20 to +20)
-                let gain_db = (gain_normalized - 0.5) * 40.0;
-                if gain_db.abs() < 0.1 {
-                    continue; // Skip if gain is essentially zero
+    /// Apply bass boost: `bass_boost` 0..1 maps to 0..+20 dB. Nothing is clipped
+    /// here; the limiter and attenuator stages own the output level.
+    fn apply_bass_boost(audio_buffer: &mut AudioBuffer, effects: &AdvancedAudioEffects) {
+        let sample_rate = audio_buffer.sample_rate as f64;
+        let frequency = effects.bass_boost_frequency.unwrap_or(200.0) as f64;
+        let gain_db = effects.bass_boost as f64 * 20.0;
+
+        match effects.bass_boost_mode.unwrap_or(BassBoostMode::Shelf) {
+            BassBoostMode::Shelf => {
+                // RBJ shelf slope S to Q
+                let slope = effects.bass_boost_slope.unwrap_or(1.0).clamp(0.1, 1.0) as f64;
+                let a = 10.0_f64.powf(gain_db / 40.0);
+                let q = 1.0 / ((a + 1.0 / a) * (1.0 / slope - 1.0) + 2.0).sqrt();
+
+                let mut filters = vec![BiquadCoefficients::low_shelf(sample_rate, frequency, gain_db, q)];
+                if let Some(resonance) = effects.bass_boost_resonance.filter(|db| db.abs() > 0.01) {
+                    filters.push(BiquadCoefficients::peak(sample_rate, frequency, resonance as f64, BASS_RESONANCE_Q));
                 }
 
-                let gain_linear = 10.0_f32.powf(gain_db / 20.0);
-                
-                // Simple peaking filter implementation
-                let omega = 2.0 * PI * freq / sample_rate;
-                let alpha = omega.sin() / (2.0 * 0.7); // Q factor of 0.7
-                let cos_w = omega.cos();
-                let a = gain_linear.sqrt();
-
-                // Filter coefficients
-                let b0 = 1.0 + alpha * a;
-                let b1 = -2.0 * cos_w;
-                let b2 = 1.0 - alpha * a;
-                let a0 = 1.0 + alpha / a;
-                let a1 = -2.0 * cos_w;
-                let a2 = 1.0 - alpha / a;
-
-                // Apply biquad filter
-                let mut x1 = 0.0;
-                let mut x2 = 0.0;
-                let mut y1 = 0.0;
-                let mut y2 = 0.0;
-
-                for (i, sample) in filtered_channel.iter_mut().enumerate() {
-                    let x0 = channel[i];
-                    let y0 = (b0 * x0 + b1 * x1 + b2 * x2 - a1 * y1 - a2 * y2) / a0;
-
-                    *sample = y0.clamp(-1.0, 1.0);
-
-                    x2 = x1;
-                    x1 = x0;
-                    y2 = y1;
-                    y1 = y0;
+                for channel in &mut audio_buffer.channels {
+                    for &coefficients in &filters {
+                        Biquad::new(coefficients).process_buffer(channel);
+                    }
                 }
             }
+            BassBoostMode::Harmonic => {
+                let level = 10.0_f32.powf(gain_db as f32 / 20.0) - 1.0;
+                for channel in &mut audio_buffer.channels {
+                    let harmonics = Self::bass_harmonics(channel, sample_rate, frequency);
+                    for (sample, harmonic) in channel.iter_mut().zip(harmonics) {
+                        *sample += harmonic * level;
+                    }
+                }
+            }
+        }
+    }
 
-            *channel = filtered_channel;
+    /// Harmonics of the content below `frequency`, band-limited to the octaves above it
+    fn bass_harmonics(channel: &[f32], sample_rate: f64, frequency: f64) -> Vec<f32> {
+        let mut bass = channel.to_vec();
+        for _ in 0..2 {
+            Biquad::new(BiquadCoefficients::low_pass(sample_rate, frequency, 0.707)).process_buffer(&mut bass);
         }
 
-        Ok(())
+        // Rectification adds even harmonics, the soft clipper odd ones
+        let mut harmonics: Vec<f32> = bass
+            .iter()
+            .map(|&b| b.abs() + 0.5 * (2.0 * b).tanh())
+            .collect();
+
+        // Keep the new partials, drop DC and the original fundamental
+        for coefficients in [
+            BiquadCoefficients::high_pass(sample_rate, frequency, 0.707),
+            BiquadCoefficients::high_pass(sample_rate, frequency, 0.707),
+            BiquadCoefficients::low_pass(sample_rate, frequency * 4.0, 0.707),
+        ] {
+            Biquad::new(coefficients).process_buffer(&mut harmonics);
+        }
+
+        harmonics
     }
 
-    /// Apply simple limiter
-    fn apply_limiter(
-        audio_buffer: &mut AudioBuffer,
-        effects: &AdvancedAudioEffects,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let threshold = effects.limiter_threshold.unwrap_or(-1.0);
-        let threshold_linear = 10.0_f32.powf(threshold / 20.0);
-        let ratio = 20.0; // Hard limiting
-        let attack_time = 0.001; // 1ms attack
-        let release_time = effects.limiter_release.unwrap_or(0.1); // 100ms release
+    /// Apply the noise gate / downward expander
+    fn apply_gate(audio_buffer: &mut AudioBuffer, effects: &AdvancedAudioEffects) {
+        let settings = GateSettings {
+            threshold_db: effects.gate_threshold.unwrap_or(-50.0),
+            range_db: effects.gate_range.unwrap_or(40.0),
+            ratio: effects.gate_ratio.unwrap_or(4.0),
+            attack_ms: effects.gate_attack.unwrap_or(1.0),
+            hold_ms: effects.gate_hold.unwrap_or(50.0),
+            release_ms: effects.gate_release.unwrap_or(200.0),
+            lookahead_ms: effects.gate_lookahead.unwrap_or(5.0),
+            sidechain_hpf: effects.gate_sidechain_hpf,
+            sidechain_lpf: effects.gate_sidechain_lpf,
+        };
+
+        gate::apply_gate(&mut audio_buffer.channels, audio_buffer.sample_rate, settings);
+    }
 
-        let sample_rate = audio_buffer.sample_rate as f32;
-        let attack_coeff = (-1.0 / (attack_time * sample_rate)).exp();
-        let release_coeff = (-1.0 / (release_time * sample_rate)).exp();
+    /// Apply the compressor; returns its gain reduction trace
+    fn apply_compressor(audio_buffer: &mut AudioBuffer, effects: &AdvancedAudioEffects) -> GainReductionTrace {
+        let settings = CompressorSettings {
+            threshold_db: effects.compressor_threshold.unwrap_or(-18.0),
+            ratio: effects.compressor_ratio.unwrap_or(4.0),
+            knee_db: effects.compressor_knee.unwrap_or(6.0),
+            attack_ms: effects.compressor_attack.unwrap_or(10.0),
+            release_ms: effects.compressor_release.unwrap_or(100.0),
+            makeup_db: effects.compressor_makeup.unwrap_or(0.0),
+            auto_makeup: effects.compressor_auto_makeup.unwrap_or(false),
+            detection: effects.compressor_detection.unwrap_or(DetectionMode::Rms),
+            sidechain_hpf: effects.compressor_sidechain_hpf,
+        };
 
-        for channel in &mut audio_buffer.channels {
-            let mut envelope = 0.0;
+        let sample_rate = audio_buffer.sample_rate;
+        stereo::with_mid_side(&mut audio_buffer.channels, effects.compressor_mid_side, |channels| {
+            compressor::apply_compressor(channels, sample_rate, settings)
+        })
+    }
 
-            for sample in channel.iter_mut() {
-                let input_level = sample.abs();
-                
-                // Envelope follower
-                if input_level > envelope {
-                    envelope = attack_coeff * envelope + (1.0 - attack_coeff) * input_level;
-                } else {
-                    envelope = release_coeff * envelope + (1.0 - release_coeff) * input_level;
-                }
+    /// LFO rate in Hz: one cycle per `sync` note when synced to `bpm`, otherwise `rate` or the default
+    fn lfo_rate(effects: &AdvancedAudioEffects, rate: Option<f32>, sync: Option<NoteValue>, default_hz: f32) -> f32 {
+        match (sync, effects.bpm) {
+            (Some(note), Some(bpm)) => 1.0 / note.seconds(bpm),
+            _ => rate.unwrap_or(default_hz),
+        }
+    }
 
-                // Compression
-                if envelope > threshold_linear {
-                    let excess = envelope / threshold_linear;
-                    let compressed_excess = excess.powf(1.0 / ratio);
-                    let gain_reduction = compressed_excess / excess;
-                    *sample *= gain_reduction;
-                }
+    /// Apply the multiband compressor; returns each band's gain reduction trace
+    fn apply_multiband_compressor(
+        audio_buffer: &mut AudioBuffer,
+        effects: &AdvancedAudioEffects,
+    ) -> Result<Vec<GainReductionTrace>, Box<dyn std::error::Error>> {
+        let crossovers = effects.multiband_crossovers.clone().unwrap_or_else(|| vec![200.0, 2000.0]);
+        let bands = effects.multiband_bands.clone().unwrap_or_default();
 
-                // Hard limit at threshold
-                *sample = sample.clamp(-threshold_linear, threshold_linear);
-            }
-        }
+        multiband::apply_multiband(&mut audio_buffer.channels, audio_buffer.sample_rate, &crossovers, &bands)
+    }
 
-        Ok(())
+    /// Apply the lookahead true-peak limiter; returns the maximum gain reduction in dB
+    fn apply_limiter(audio_buffer: &mut AudioBuffer, effects: &AdvancedAudioEffects) -> f32 {
+        let settings = LimiterSettings {
+            threshold_db: effects.limiter_threshold.unwrap_or(-1.0),
+            lookahead_ms: effects.limiter_lookahead.unwrap_or(5.0),
+            release: effects.limiter_release.unwrap_or(0.1), // 100ms release
+        };
+
+        limiter::apply_limiter(&mut audio_buffer.channels, audio_buffer.sample_rate, settings)
     }
 
     /// Apply attenuator (gain)
@@ -258,43 +533,71 @@ impl AudioProcessor {
         Ok(())
     }
 
-    /// Apply simple reverb using convolution
+    /// Apply convolution reverb with the user's impulse response or a generated one
     fn apply_reverb(
         audio_buffer: AudioBuffer,
-        reverb_amount: f32,
+        effects: &AdvancedAudioEffects,
+        dry_level: f32,
+        wet_level: f32,
     ) -> Result<AudioBuffer, Box<dyn std::error::Error>> {
         let sample_rate = audio_buffer.sample_rate;
-        let reverb_length = (sample_rate as f32 * 2.0) as usize; // 2 second reverb
-        
-        // Generate impulse response
-        let mut impulse = vec![0.0; reverb_length];
-        for i in 0..reverb_length {
-            let decay = (-3.0 * i as f32 / reverb_length as f32).exp();
-            impulse[i] = (rand::random::<f32>() * 2.0 - 1.0) * decay;
-        }
 
-        let mut processed_channels = Vec::new();
+        let mut impulse = match &effects.reverb_ir_path {
+            Some(path) => Self::load_impulse_response(path, sample_rate, effects)?,
+            None => Self::generate_impulse(
+                sample_rate,
+                audio_buffer.channels.len().min(2),
+                effects.reverb_seed.unwrap_or(0),
+                effects.reverb_width.unwrap_or(1.0),
+            ),
+        };
+
+        let pre_delay = (effects.reverb_pre_delay.unwrap_or(0.0).max(0.0) * 0.001 * sample_rate as f32) as usize;
+        for channel in &mut impulse {
+            channel.splice(0..0, std::iter::repeat_n(0.0, pre_delay));
+        }
 
-        for channel in &audio_buffer.channels {
-            let mut processed_channel = vec![0.0; channel.len() + reverb_length];
-            
-            // Simple convolution
-            for (i, &sample) in channel.iter().enumerate() {
-                for (j, &impulse_sample) in impulse.iter().enumerate() {
-                    if i + j < processed_channel.len() {
-                        processed_channel[i + j] += sample * impulse_sample * reverb_amount;
-                    }
-                }
+        let convolvers = impulse
+            .iter()
+            .map(|channel| PartitionedConvolver::new(channel))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let channels = &audio_buffer.channels;
+        let len = channels.first().map_or(0, |c| c.len());
+        let wet_channels: Vec<Vec<f32>> = if convolvers.len() == 4 && channels.len() == 2 {
+            // True stereo: each output side hears both inputs through its own path
+            let mut wet_left = convolvers[0].process(&channels[0], len)?;
+            let mut wet_right = convolvers[1].process(&channels[0], len)?;
+            for (wet, sample) in wet_left.iter_mut().zip(convolvers[2].process(&channels[1], len)?) {
+                *wet += sample;
             }
+            for (wet, sample) in wet_right.iter_mut().zip(convolvers[3].process(&channels[1], len)?) {
+                *wet += sample;
+            }
+            vec![wet_left, wet_right]
+        } else {
+            // A mono IR feeds every channel; stereo IRs alternate left/right across channels
+            let paths: Vec<&PartitionedConvolver> = match convolvers.len() {
+                4 => vec![&convolvers[0], &convolvers[3]],
+                _ => convolvers.iter().collect(),
+            };
+            channels
+                .iter()
+                .enumerate()
+                .map(|(i, channel)| paths[i % paths.len()].process(channel, len))
+                .collect::<Result<_, _>>()?
+        };
+
+        let mut processed_channels = Vec::new();
 
+        for (channel, wet) in channels.iter().zip(&wet_channels) {
             // Mix with dry signal
-            for i in 0..channel.len() {
-                processed_channel[i] = channel[i] * (1.0 - reverb_amount) + 
-                                      processed_channel[i] * reverb_amount;
-            }
+            let processed_channel: Vec<f32> = channel
+                .iter()
+                .zip(wet)
+                .map(|(&dry, &wet)| dry * dry_level + wet * wet_level)
+                .collect();
 
-            // Trim to original length
-            processed_channel.truncate(channel.len());
             processed_channels.push(processed_channel);
         }
 
@@ -304,4 +607,111 @@ impl AudioProcessor {
             duration: audio_buffer.duration,
         })
     }
+
+    /// Two-second decaying noise burst used when no IR file is given, one per output side.
+    /// Each side blends a shared noise sequence with its own by `width`, at constant energy.
+    fn generate_impulse(sample_rate: u32, sides: usize, seed: u32, width: f32) -> Vec<Vec<f32>> {
+        let reverb_length = (sample_rate as f32 * 2.0) as usize; // 2 second reverb
+        let mut rng = StdRng::seed_from_u64(seed as u64);
+        let noise = |rng: &mut StdRng| -> Vec<f32> {
+            (0..reverb_length).map(|_| rng.gen::<f32>() * 2.0 - 1.0).collect()
+        };
+
+        let shared = noise(&mut rng);
+        let angle = width.clamp(0.0, 1.0) * PI / 2.0;
+        let (shared_gain, own_gain) = (angle.cos(), angle.sin());
+
+        (0..sides.max(1))
+            .map(|_| {
+                let own = noise(&mut rng);
+                shared
+                    .iter()
+                    .zip(&own)
+                    .enumerate()
+                    .map(|(i, (&shared, &own))| {
+                        let decay = (-3.0 * i as f32 / reverb_length as f32).exp();
+                        (shared * shared_gain + own * own_gain) * decay
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Load an IR file, trim it, and resample/stretch it to the buffer's sample rate
+    fn load_impulse_response(
+        path: &str,
+        sample_rate: u32,
+        effects: &AdvancedAudioEffects,
+    ) -> Result<Vec<Vec<f32>>, Box<dyn std::error::Error>> {
+        let impulse = AudioLoader::load_audio_file(path)?;
+        if !matches!(impulse.channels.len(), 1 | 2 | 4) {
+            return Err(format!(
+                "Unsupported impulse response channel count: {} (expected 1, 2 or 4)",
+                impulse.channels.len()
+            )
+            .into());
+        }
+
+        let ms_to_frames = |ms: f32| (ms.max(0.0) * 0.001 * impulse.sample_rate as f32) as usize;
+        let start = effects.reverb_ir_trim_start.map_or(0, ms_to_frames);
+        let length = effects.reverb_ir_length.map(ms_to_frames);
+        let fade = ms_to_frames(IR_FADE_MS);
+
+        let mut trimmed: Vec<Vec<f32>> = impulse
+            .channels
+            .iter()
+            .map(|channel| {
+                let start = start.min(channel.len());
+                let end = length.map_or(channel.len(), |length| (start + length).min(channel.len()));
+                channel[start..end].to_vec()
+            })
+            .collect();
+
+        // Fade out a shortened tail so the cut does not click
+        if length.is_some() {
+            for channel in &mut trimmed {
+                let fade = fade.min(channel.len());
+                let fade_start = channel.len() - fade;
+                for (i, sample) in channel[fade_start..].iter_mut().enumerate() {
+                    *sample *= 1.0 - (i + 1) as f32 / fade as f32;
+                }
+            }
+        }
+
+        let stretch = effects.reverb_ir_stretch.unwrap_or(1.0).clamp(0.25, 4.0) as f64;
+        let ratio = sample_rate as f64 / impulse.sample_rate as f64 * stretch;
+        resampler::resample_channels(&trimmed, ratio)
+    }
+
+    /// Apply the Freeverb-style reverb; channels are processed in stereo pairs
+    fn apply_algorithmic_reverb(
+        mut audio_buffer: AudioBuffer,
+        settings: FreeverbSettings,
+        dry_level: f32,
+        wet_level: f32,
+    ) -> AudioBuffer {
+        let sample_rate = audio_buffer.sample_rate;
+
+        for pair in audio_buffer.channels.chunks_mut(2) {
+            let mut reverb = Freeverb::new(sample_rate, settings);
+            match pair {
+                [left, right] => {
+                    for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+                        let (wet_left, wet_right) = reverb.process(*l, *r);
+                        *l = *l * dry_level + wet_left * wet_level;
+                        *r = *r * dry_level + wet_right * wet_level;
+                    }
+                }
+                [mono] => {
+                    for sample in mono.iter_mut() {
+                        let (wet_left, wet_right) = reverb.process(*sample, *sample);
+                        *sample = *sample * dry_level + 0.5 * (wet_left + wet_right) * wet_level;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        audio_buffer
+    }
 }
\ No newline at end of file
@@ -8,14 +8,210 @@ pub struct AudioEffects {
     pub volume: f32,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TempoMode {
+    /// Speed and pitch change together (tape-style)
+    Varispeed,
+    /// Speed changes, pitch is preserved
+    TimeStretch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BassBoostMode {
+    /// Low-shelf boost of the actual low end
+    Shelf,
+    /// Adds harmonics of the low end so it is heard on speakers that cannot reproduce it
+    Harmonic,
+}
+
+/// Musical note length, for effects synced to `bpm`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NoteValue {
+    #[serde(rename = "1/1")]
+    Whole,
+    #[serde(rename = "1/2")]
+    Half,
+    #[serde(rename = "1/4")]
+    Quarter,
+    #[serde(rename = "1/8")]
+    Eighth,
+    #[serde(rename = "1/16")]
+    Sixteenth,
+    #[serde(rename = "1/32")]
+    ThirtySecond,
+    #[serde(rename = "1/4d")]
+    DottedQuarter,
+    #[serde(rename = "1/8d")]
+    DottedEighth,
+    #[serde(rename = "1/16d")]
+    DottedSixteenth,
+    #[serde(rename = "1/4t")]
+    QuarterTriplet,
+    #[serde(rename = "1/8t")]
+    EighthTriplet,
+    #[serde(rename = "1/16t")]
+    SixteenthTriplet,
+}
+
+impl NoteValue {
+    /// Length in quarter-note beats
+    pub fn beats(self) -> f32 {
+        match self {
+            NoteValue::Whole => 4.0,
+            NoteValue::Half => 2.0,
+            NoteValue::Quarter => 1.0,
+            NoteValue::Eighth => 0.5,
+            NoteValue::Sixteenth => 0.25,
+            NoteValue::ThirtySecond => 0.125,
+            NoteValue::DottedQuarter => 1.5,
+            NoteValue::DottedEighth => 0.75,
+            NoteValue::DottedSixteenth => 0.375,
+            NoteValue::QuarterTriplet => 2.0 / 3.0,
+            NoteValue::EighthTriplet => 1.0 / 3.0,
+            NoteValue::SixteenthTriplet => 1.0 / 6.0,
+        }
+    }
+
+    /// Length in seconds at `bpm`
+    pub fn seconds(self, bpm: f32) -> f32 {
+        self.beats() * 60.0 / bpm.max(1.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SaturationCurve {
+    /// Smooth symmetric tanh curve, odd harmonics
+    Tape,
+    /// Biased tanh, adds even harmonics
+    Tube,
+    /// Cubic curve that reaches full scale gently
+    SoftClip,
+    /// Flat cut at full scale
+    HardClip,
+    /// Peaks over full scale are folded back down
+    Foldback,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MidSideChannel {
+    /// (L + R) / 2, the center of the image
+    Mid,
+    /// (L - R) / 2, what differs between the sides
+    Side,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanLaw {
+    /// Only turns the far side down; the near side stays at unity
+    Balance,
+    /// Equal power: the near side rises by up to 3 dB as the far side fades
+    ConstantPower,
+    /// Equal amplitude: the near side rises by up to 6 dB
+    Linear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DetectionMode {
+    /// Follow the instantaneous peak level
+    Peak,
+    /// Follow the short-term average power
+    Rms,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EqBandType {
+    Peak,
+    LowShelf,
+    HighShelf,
+    LowPass,
+    HighPass,
+    Notch,
+    BandPass,
+    /// Gentle slope pivoting at the band frequency: -gain/2 at the bottom, +gain/2 at the top
+    Tilt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EqBand {
+    #[serde(rename = "type")]
+    pub band_type: EqBandType,
+    /// Center, corner or pivot frequency in Hz
+    pub frequency: f32,
+    /// Ignored by the pass, notch and band-pass types
+    pub gain_db: f32,
+    /// RBJ Q: bandwidth for peak/notch/band-pass, resonance for the pass types and
+    /// shelf steepness for shelves (0.707 is the steepest shelf without overshoot)
+    pub q: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReverbType {
+    /// Convolution with a generated impulse response
+    Convolution,
+    /// Freeverb-style comb/all-pass network
+    Algorithmic,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AdvancedAudioEffects {
     pub reverb: f32,
+    /// Reverb engine; defaults to convolution
+    pub reverb_type: Option<ReverbType>,
+    /// Algorithmic room size 0..1 (longer decay when larger)
+    pub reverb_room_size: Option<f32>,
+    /// Algorithmic high-frequency damping 0..1
+    pub reverb_damping: Option<f32>,
+    /// Gap before the reverb tail in milliseconds
+    pub reverb_pre_delay: Option<f32>,
+    /// Stereo width of the reverb tail 0..1
+    pub reverb_width: Option<f32>,
+    /// Seed of the generated impulse response, so renders are reproducible
+    pub reverb_seed: Option<u32>,
+    /// Dry level; defaults to `1 - reverb`
+    pub reverb_dry: Option<f32>,
+    /// Wet level; defaults to `reverb * reverb` for the convolution engine and `reverb` for the algorithmic one
+    pub reverb_wet: Option<f32>,
+    /// Impulse response file for the convolution reverb: mono, stereo, or
+    /// true-stereo 4-channel (L→L, L→R, R→L, R→R). A generated IR is used when unset.
+    pub reverb_ir_path: Option<String>,
+    /// Milliseconds cut from the start of the loaded IR
+    pub reverb_ir_trim_start: Option<f32>,
+    /// Maximum length of the loaded IR in milliseconds (faded out at the cut)
+    pub reverb_ir_length: Option<f32>,
+    /// Time-stretch factor of the loaded IR (above 1.0 is a longer, darker tail)
+    pub reverb_ir_stretch: Option<f32>,
     pub bass_boost: f32,
+    /// Bass boost style; defaults to a low shelf
+    pub bass_boost_mode: Option<BassBoostMode>,
+    /// Shelf corner (midpoint) frequency in Hz. Defaults to 200.
+    pub bass_boost_frequency: Option<f32>,
+    /// Shelf slope 0.1..1 (1 is the steepest without overshoot). Defaults to 1.
+    pub bass_boost_slope: Option<f32>,
+    /// Extra resonant bump at the corner frequency in dB
+    pub bass_boost_resonance: Option<f32>,
     pub tempo: f32,
     pub volume: f32,
+    /// How `tempo` is applied; defaults to varispeed
+    pub tempo_mode: Option<TempoMode>,
+    /// Song tempo in beats per minute, used by tempo-synced effects
+    pub bpm: Option<f32>,
     
     // EQ Bands
+    /// Parametric EQ bands, applied in series after the five fixed bands below
+    pub eq_bands: Option<Vec<EqBand>>,
+    /// Render the EQ as a linear-phase FIR instead of minimum-phase biquads
+    pub eq_linear_phase: Option<bool>,
+    /// Run the EQ on the mid or side signal only
+    pub eq_mid_side: Option<MidSideChannel>,
+    /// Fixed bands, 0..1 mapped to -20..+20 dB (0.5 is flat)
     pub eq_low: Option<f32>,
     pub eq_low_mid: Option<f32>,
     pub eq_mid: Option<f32>,
@@ -23,22 +219,217 @@ pub struct AdvancedAudioEffects {
     pub eq_high: Option<f32>,
     
     // Nightcore
+    /// Coupled speed and pitch ratio: above 1.0 is nightcore, below is daycore
     pub nightcore: Option<f32>,
+    /// Nightcore amount in semitones; overrides `nightcore` when set
+    pub nightcore_semitones: Option<f32>,
+    /// Tempo-independent pitch shift in semitones
     pub pitch_shift: Option<f32>,
+    pub pitch_shift_cents: Option<f32>,
+    /// Keep drum hits sharp in pitch shifting and time stretching (default on)
+    pub pitch_transient_handling: Option<bool>,
     
     // Pitcher
+    /// Formant-preserving pitch shift in semitones
     pub pitcher: Option<f32>,
+    /// Formant shift in semitones, independent of pitch
     pub formant_shift: Option<f32>,
     
     // Vocal Extractor
+    /// Enable the center-channel vocal/instrumental separation stage
     pub vocal_extractor: Option<bool>,
+    /// Mask leniency 0..1: higher values also take roughly centered content as vocal
     pub vocal_sensitivity: Option<f32>,
+    /// Output mix 0..1 from vocal only (0) to instrumental only (1)
     pub instrumental_separation: Option<f32>,
     
     // New Audio Processing Features
     pub limiter: Option<bool>,
+    /// Ceiling in dBTP. Defaults to -1.
     pub limiter_threshold: Option<f32>,
+    /// Release time in seconds. Defaults to 0.1.
     pub limiter_release: Option<f32>,
+    /// Lookahead in milliseconds. Defaults to 5.
+    pub limiter_lookahead: Option<f32>,
+
+    // Noise gate / downward expander
+    pub gate: Option<bool>,
+    /// Threshold in dBFS. Defaults to -50.
+    pub gate_threshold: Option<f32>,
+    /// Largest attenuation in dB. Defaults to 40.
+    pub gate_range: Option<f32>,
+    /// Expansion ratio below the threshold. Defaults to 4.
+    pub gate_ratio: Option<f32>,
+    /// Attack (opening) time in milliseconds. Defaults to 1.
+    pub gate_attack: Option<f32>,
+    /// Time in milliseconds the gate stays open after the signal drops. Defaults to 50.
+    pub gate_hold: Option<f32>,
+    /// Release (closing) time in milliseconds. Defaults to 200.
+    pub gate_release: Option<f32>,
+    /// Lookahead in milliseconds. Defaults to 5.
+    pub gate_lookahead: Option<f32>,
+    /// Sidechain high-pass corner in Hz
+    pub gate_sidechain_hpf: Option<f32>,
+    /// Sidechain low-pass corner in Hz, so hiss does not hold the gate open
+    pub gate_sidechain_lpf: Option<f32>,
+
+    // Saturation / distortion
+    pub saturation: Option<bool>,
+    /// Defaults to tape
+    pub saturation_curve: Option<SaturationCurve>,
+    /// Gain into the curve in dB. Defaults to 6.
+    pub saturation_drive: Option<f32>,
+    /// Gain after the curve in dB. Defaults to 0.
+    pub saturation_output: Option<f32>,
+    /// 0 (dry) to 1 (wet). Defaults to 1.
+    pub saturation_mix: Option<f32>,
+    /// Oversampling factor: 1, 2, 4 or 8. Defaults to 4. Also used by the
+    /// bitcrusher when it only reduces the bit depth.
+    pub saturation_oversampling: Option<u32>,
+
+    // Bitcrusher
+    pub bitcrusher: Option<bool>,
+    /// Bit depth, 1 to 24. Defaults to 8.
+    pub bitcrusher_bits: Option<u32>,
+    /// Reduced sample rate in Hz; the original rate is kept (and the quantizer oversampled) when unset
+    pub bitcrusher_sample_rate: Option<f32>,
+    /// 0 (dry) to 1 (wet). Defaults to 1.
+    pub bitcrusher_mix: Option<f32>,
+
+    // Compressor
+    pub compressor: Option<bool>,
+    /// Threshold in dBFS. Defaults to -18.
+    pub compressor_threshold: Option<f32>,
+    /// Compression ratio (4 means 4:1). Defaults to 4.
+    pub compressor_ratio: Option<f32>,
+    /// Soft knee width in dB. Defaults to 6.
+    pub compressor_knee: Option<f32>,
+    /// Attack time in milliseconds. Defaults to 10.
+    pub compressor_attack: Option<f32>,
+    /// Release time in milliseconds. Defaults to 100.
+    pub compressor_release: Option<f32>,
+    /// Fixed makeup gain in dB, added to the automatic makeup when that is on
+    pub compressor_makeup: Option<f32>,
+    /// Make up for the gain the compressor takes from a full-scale signal
+    pub compressor_auto_makeup: Option<bool>,
+    /// Level detector; defaults to RMS
+    pub compressor_detection: Option<DetectionMode>,
+    /// Sidechain high-pass corner in Hz, so bass does not drive the compressor
+    pub compressor_sidechain_hpf: Option<f32>,
+    /// Compress the mid or side signal only
+    pub compressor_mid_side: Option<MidSideChannel>,
+
+    // Multiband compressor
+    pub multiband_compressor: Option<bool>,
+    /// Two or three ascending crossover frequencies in Hz (3 or 4 bands). Defaults to 200 and 2000.
+    pub multiband_crossovers: Option<Vec<f32>>,
+    /// Settings per band, lowest first; missing bands use gentle defaults
+    pub multiband_bands: Option<Vec<MultibandBand>>,
+
+    // Chorus
+    pub chorus: Option<bool>,
+    /// LFO rate in Hz. Defaults to 0.8.
+    pub chorus_rate: Option<f32>,
+    /// LFO cycle as a note value; overrides the rate when `bpm` is set
+    pub chorus_sync: Option<NoteValue>,
+    /// 0..1. Defaults to 0.5.
+    pub chorus_depth: Option<f32>,
+    /// -0.95..0.95. Defaults to 0.
+    pub chorus_feedback: Option<f32>,
+    /// 0 (dry) to 1 (wet). Defaults to 0.5.
+    pub chorus_mix: Option<f32>,
+    /// LFO phase offset between left and right in degrees. Defaults to 90.
+    pub chorus_stereo_phase: Option<f32>,
+
+    // Flanger
+    pub flanger: Option<bool>,
+    /// LFO rate in Hz. Defaults to 0.25.
+    pub flanger_rate: Option<f32>,
+    /// LFO cycle as a note value; overrides the rate when `bpm` is set
+    pub flanger_sync: Option<NoteValue>,
+    /// 0..1. Defaults to 0.7.
+    pub flanger_depth: Option<f32>,
+    /// -0.95..0.95. Defaults to 0.5.
+    pub flanger_feedback: Option<f32>,
+    /// 0 (dry) to 1 (wet). Defaults to 0.5.
+    pub flanger_mix: Option<f32>,
+    /// LFO phase offset between left and right in degrees. Defaults to 90.
+    pub flanger_stereo_phase: Option<f32>,
+
+    // Phaser
+    pub phaser: Option<bool>,
+    /// LFO rate in Hz. Defaults to 0.5.
+    pub phaser_rate: Option<f32>,
+    /// LFO cycle as a note value; overrides the rate when `bpm` is set
+    pub phaser_sync: Option<NoteValue>,
+    /// 0..1. Defaults to 0.8.
+    pub phaser_depth: Option<f32>,
+    /// -0.95..0.95. Defaults to 0.4.
+    pub phaser_feedback: Option<f32>,
+    /// 0 (dry) to 1 (wet). Defaults to 0.5.
+    pub phaser_mix: Option<f32>,
+    /// LFO phase offset between left and right in degrees. Defaults to 90.
+    pub phaser_stereo_phase: Option<f32>,
+
+    // Delay / echo
+    pub delay: Option<bool>,
+    /// Delay time in milliseconds. Defaults to 375.
+    pub delay_time: Option<f32>,
+    /// Delay time as a note value; overrides the time when `bpm` is set
+    pub delay_sync: Option<NoteValue>,
+    /// 0..0.95. Defaults to 0.4.
+    pub delay_feedback: Option<f32>,
+    /// 0 (dry) to 1 (wet). Defaults to 0.3.
+    pub delay_mix: Option<f32>,
+    /// Alternate repeats between left and right
+    pub delay_ping_pong: Option<bool>,
+    /// High-pass corner in the feedback path, in Hz
+    pub delay_low_cut: Option<f32>,
+    /// Low-pass corner in the feedback path, in Hz
+    pub delay_high_cut: Option<f32>,
+    /// Most the echoes are turned down while the dry signal is loud, in dB. Defaults to 0 (off).
+    pub delay_ducking: Option<f32>,
+    /// Dry level in dBFS where ducking starts. Defaults to -30.
+    pub delay_duck_threshold: Option<f32>,
+
+    // Lo-fi vinyl / tape
+    pub lofi: Option<bool>,
+    /// Seed for crackle, hiss and wobble phase; the same seed renders the same result. Defaults to 0.
+    pub lofi_seed: Option<u32>,
+    /// Crackle events per second. Defaults to 8.
+    pub lofi_crackle_density: Option<f32>,
+    /// Peak crackle level in dBFS. Defaults to -30.
+    pub lofi_crackle_level: Option<f32>,
+    /// Tape hiss level in dBFS; no hiss when unset
+    pub lofi_hiss: Option<f32>,
+    /// Wow rate in Hz. Defaults to 0.5.
+    pub lofi_wow_rate: Option<f32>,
+    /// Wow depth in cents. Defaults to 8.
+    pub lofi_wow_depth: Option<f32>,
+    /// Flutter rate in Hz. Defaults to 6.
+    pub lofi_flutter_rate: Option<f32>,
+    /// Flutter depth in cents. Defaults to 3.
+    pub lofi_flutter_depth: Option<f32>,
+    /// Drive into the tape saturation curve in dB. Defaults to 3.
+    pub lofi_tape_drive: Option<f32>,
+    /// Band-limit high-pass corner in Hz. Defaults to 80.
+    pub lofi_low_cut: Option<f32>,
+    /// Band-limit low-pass corner in Hz. Defaults to 8000.
+    pub lofi_high_cut: Option<f32>,
+
+    // Stereo image
+    pub stereo: Option<bool>,
+    /// Width in percent, 0 (mono) to 200. Defaults to 100.
+    pub stereo_width: Option<f32>,
+    /// Mid gain in dB
+    pub stereo_mid_gain: Option<f32>,
+    /// Side gain in dB
+    pub stereo_side_gain: Option<f32>,
+    /// -1 (left) to 1 (right). Defaults to 0.
+    pub stereo_balance: Option<f32>,
+    /// Defaults to balance
+    pub stereo_pan_law: Option<PanLaw>,
+
     pub attenuator: Option<bool>,
     pub attenuator_gain: Option<f32>,
     pub audio_processing_enabled: Option<bool>,
@@ -51,6 +442,127 @@ pub struct AudioBuffer {
     pub duration: f32,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkerKind {
+    Marker,
+    Region,
+    Loop,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioMarker {
+    pub id: u32,
+    pub kind: MarkerKind,
+    pub label: String,
+    /// Start position in sample frames
+    pub position: u32,
+    /// Span in sample frames (regions and loops only)
+    pub length: Option<u32>,
+}
+
+/// A decoded file together with the markers stored in it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadedAudio {
+    #[serde(flatten)]
+    pub buffer: AudioBuffer,
+    pub markers: Vec<AudioMarker>,
+}
+
+/// Settings and provenance embedded in exported files so a render can be reproduced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingRecipe {
+    pub effects: AdvancedAudioEffects,
+    pub app_version: String,
+    /// SHA-256 of the source file, hex encoded
+    pub source_hash: Option<String>,
+    /// Unix timestamp (seconds) of the render
+    pub processed_at: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Wav,
+    Mp3,
+}
+
+/// One output of a multi-target export job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportTarget {
+    pub format: ExportFormat,
+    /// WAV bit depth: 16, 24 or 32 (float). Defaults to 32.
+    pub bit_depth: Option<u16>,
+    /// MP3 bitrate in kbps. Defaults to 320.
+    pub bitrate: Option<u32>,
+    /// Output sample rate; the processed rate is kept when unset
+    pub sample_rate: Option<u32>,
+    /// Integrated loudness target in LUFS
+    pub loudness_target: Option<f32>,
+    /// Output path; `{name}`, `{ext}`, `{sample_rate}`, `{bit_depth}` and `{bitrate}` are substituted
+    pub path_template: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StemSeparationSettings {
+    /// Path to the ONNX separation model (waveform in `[1, 2, samples]`,
+    /// stems out `[1, 4, 2, samples]` in drums, bass, other, vocals order)
+    pub model_path: String,
+    /// Sample rate the model was trained at. Defaults to 44100.
+    pub model_sample_rate: Option<u32>,
+    /// Length of each chunk fed to the model in seconds. Defaults to 7.8.
+    pub segment_seconds: Option<f32>,
+    /// Fraction of each chunk shared with its neighbour (0..0.5). Defaults to 0.25.
+    pub overlap: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StemSet {
+    pub vocals: AudioBuffer,
+    pub drums: AudioBuffer,
+    pub bass: AudioBuffer,
+    pub other: AudioBuffer,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultibandBand {
+    /// Threshold in dBFS
+    pub threshold: f32,
+    pub ratio: f32,
+    /// Attack time in milliseconds
+    pub attack: f32,
+    /// Release time in milliseconds
+    pub release: f32,
+    /// Makeup gain in dB
+    pub makeup: f32,
+}
+
+/// Measurements taken while rendering
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessingReport {
+    /// Latency of the linear-phase EQ in samples; the output is already shifted back by it
+    pub eq_latency_samples: Option<u32>,
+    /// Largest gain reduction the limiter applied, in dB
+    pub limiter_gain_reduction_db: Option<f32>,
+    /// Compressor gain reduction per block, for metering
+    pub compressor_gain_reduction: Option<GainReductionTrace>,
+    /// Multiband compressor gain reduction per band, lowest band first
+    pub multiband_gain_reduction: Option<Vec<GainReductionTrace>>,
+}
+
+/// Gain reduction over time: the largest reduction (dB, positive) in each block of samples
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GainReductionTrace {
+    pub block_size: u32,
+    pub values_db: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessedAudio {
+    pub audio_buffer: AudioBuffer,
+    pub report: ProcessingReport,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessingProgress {
     pub percentage: f32,
@@ -0,0 +1,171 @@
+use std::f64::consts::PI;
+
+/// Biquad coefficients normalized so that a0 = 1
+#[derive(Debug, Clone, Copy)]
+pub struct BiquadCoefficients {
+    pub b0: f64,
+    pub b1: f64,
+    pub b2: f64,
+    pub a1: f64,
+    pub a2: f64,
+}
+
+impl BiquadCoefficients {
+    /// Build normalized coefficients from raw (b0, b1, b2, a0, a1, a2) values
+    pub fn from_raw(b0: f64, b1: f64, b2: f64, a0: f64, a1: f64, a2: f64) -> Self {
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        }
+    }
+
+    /// RBJ peaking EQ
+    pub fn peak(sample_rate: f64, frequency: f64, gain_db: f64, q: f64) -> Self {
+        let a = 10.0_f64.powf(gain_db / 40.0);
+        let (cos_w, alpha) = Self::omega(sample_rate, frequency, q);
+        Self::from_raw(
+            1.0 + alpha * a,
+            -2.0 * cos_w,
+            1.0 - alpha * a,
+            1.0 + alpha / a,
+            -2.0 * cos_w,
+            1.0 - alpha / a,
+        )
+    }
+
+    /// RBJ low shelf; `q` of 0.707 gives the steepest slope without overshoot
+    pub fn low_shelf(sample_rate: f64, frequency: f64, gain_db: f64, q: f64) -> Self {
+        let a = 10.0_f64.powf(gain_db / 40.0);
+        let (cos_w, alpha) = Self::omega(sample_rate, frequency, q);
+        let root = 2.0 * a.sqrt() * alpha;
+        Self::from_raw(
+            a * ((a + 1.0) - (a - 1.0) * cos_w + root),
+            2.0 * a * ((a - 1.0) - (a + 1.0) * cos_w),
+            a * ((a + 1.0) - (a - 1.0) * cos_w - root),
+            (a + 1.0) + (a - 1.0) * cos_w + root,
+            -2.0 * ((a - 1.0) + (a + 1.0) * cos_w),
+            (a + 1.0) + (a - 1.0) * cos_w - root,
+        )
+    }
+
+    /// RBJ high shelf
+    pub fn high_shelf(sample_rate: f64, frequency: f64, gain_db: f64, q: f64) -> Self {
+        let a = 10.0_f64.powf(gain_db / 40.0);
+        let (cos_w, alpha) = Self::omega(sample_rate, frequency, q);
+        let root = 2.0 * a.sqrt() * alpha;
+        Self::from_raw(
+            a * ((a + 1.0) + (a - 1.0) * cos_w + root),
+            -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_w),
+            a * ((a + 1.0) + (a - 1.0) * cos_w - root),
+            (a + 1.0) - (a - 1.0) * cos_w + root,
+            2.0 * ((a - 1.0) - (a + 1.0) * cos_w),
+            (a + 1.0) - (a - 1.0) * cos_w - root,
+        )
+    }
+
+    /// RBJ second-order low-pass
+    pub fn low_pass(sample_rate: f64, frequency: f64, q: f64) -> Self {
+        let (cos_w, alpha) = Self::omega(sample_rate, frequency, q);
+        Self::from_raw(
+            (1.0 - cos_w) / 2.0,
+            1.0 - cos_w,
+            (1.0 - cos_w) / 2.0,
+            1.0 + alpha,
+            -2.0 * cos_w,
+            1.0 - alpha,
+        )
+    }
+
+    /// RBJ second-order high-pass
+    pub fn high_pass(sample_rate: f64, frequency: f64, q: f64) -> Self {
+        let (cos_w, alpha) = Self::omega(sample_rate, frequency, q);
+        Self::from_raw(
+            (1.0 + cos_w) / 2.0,
+            -(1.0 + cos_w),
+            (1.0 + cos_w) / 2.0,
+            1.0 + alpha,
+            -2.0 * cos_w,
+            1.0 - alpha,
+        )
+    }
+
+    /// RBJ notch
+    pub fn notch(sample_rate: f64, frequency: f64, q: f64) -> Self {
+        let (cos_w, alpha) = Self::omega(sample_rate, frequency, q);
+        Self::from_raw(1.0, -2.0 * cos_w, 1.0, 1.0 + alpha, -2.0 * cos_w, 1.0 - alpha)
+    }
+
+    /// RBJ band-pass with 0 dB peak gain
+    pub fn band_pass(sample_rate: f64, frequency: f64, q: f64) -> Self {
+        let (cos_w, alpha) = Self::omega(sample_rate, frequency, q);
+        Self::from_raw(alpha, 0.0, -alpha, 1.0 + alpha, -2.0 * cos_w, 1.0 - alpha)
+    }
+
+    /// RBJ second-order all-pass; with Q = 0.707 it matches the phase of an LR4 crossover pair
+    pub fn all_pass(sample_rate: f64, frequency: f64, q: f64) -> Self {
+        let (cos_w, alpha) = Self::omega(sample_rate, frequency, q);
+        Self::from_raw(1.0 - alpha, -2.0 * cos_w, 1.0 + alpha, 1.0 + alpha, -2.0 * cos_w, 1.0 - alpha)
+    }
+
+    /// First-order tilt around `frequency`: -gain/2 at DC rising to +gain/2 at Nyquist
+    pub fn tilt(sample_rate: f64, frequency: f64, gain_db: f64) -> Self {
+        let root_gain = 10.0_f64.powf(gain_db / 40.0);
+        let k = (PI * frequency.clamp(1.0, sample_rate * 0.499) / sample_rate).tan();
+        Self::from_raw(root_gain + k, k - root_gain, 0.0, 1.0 + root_gain * k, root_gain * k - 1.0, 0.0)
+    }
+
+    /// Magnitude response at `frequency`
+    pub fn magnitude(&self, sample_rate: f64, frequency: f64) -> f64 {
+        let w = 2.0 * PI * frequency / sample_rate;
+        let (cos_1, sin_1, cos_2, sin_2) = (w.cos(), w.sin(), (2.0 * w).cos(), (2.0 * w).sin());
+        let num_re = self.b0 + self.b1 * cos_1 + self.b2 * cos_2;
+        let num_im = self.b1 * sin_1 + self.b2 * sin_2;
+        let den_re = 1.0 + self.a1 * cos_1 + self.a2 * cos_2;
+        let den_im = self.a1 * sin_1 + self.a2 * sin_2;
+        ((num_re * num_re + num_im * num_im) / (den_re * den_re + den_im * den_im)).sqrt()
+    }
+
+    /// cos(w0) and the RBJ alpha term, with the frequency kept below Nyquist
+    fn omega(sample_rate: f64, frequency: f64, q: f64) -> (f64, f64) {
+        let frequency = frequency.clamp(1.0, sample_rate * 0.499);
+        let w0 = 2.0 * PI * frequency / sample_rate;
+        (w0.cos(), w0.sin() / (2.0 * q.max(0.01)))
+    }
+}
+
+/// Transposed direct form II biquad with double precision state
+#[derive(Debug, Clone)]
+pub struct Biquad {
+    coefficients: BiquadCoefficients,
+    z1: f64,
+    z2: f64,
+}
+
+impl Biquad {
+    pub fn new(coefficients: BiquadCoefficients) -> Self {
+        Self {
+            coefficients,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    #[inline]
+    pub fn process(&mut self, input: f32) -> f32 {
+        let c = &self.coefficients;
+        let x = input as f64;
+        let y = c.b0 * x + self.z1;
+        self.z1 = c.b1 * x - c.a1 * y + self.z2;
+        self.z2 = c.b2 * x - c.a2 * y;
+        y as f32
+    }
+
+    pub fn process_buffer(&mut self, samples: &mut [f32]) {
+        for sample in samples.iter_mut() {
+            *sample = self.process(*sample);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use crate::audio_types::{DetectionMode, GainReductionTrace};
+use crate::dynamics;
+
+/// Samples per gain-reduction meter value
+const METER_BLOCK: usize = 1024;
+/// Averaging time of the RMS detector in milliseconds
+const RMS_WINDOW_MS: f32 = 10.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct CompressorSettings {
+    pub threshold_db: f32,
+    pub ratio: f32,
+    pub knee_db: f32,
+    pub attack_ms: f32,
+    pub release_ms: f32,
+    pub makeup_db: f32,
+    pub auto_makeup: bool,
+    pub detection: DetectionMode,
+    /// Sidechain high-pass corner in Hz
+    pub sidechain_hpf: Option<f32>,
+}
+
+impl CompressorSettings {
+    /// Static curve: gain change in dB (zero or negative) for an input level in dB, with a quadratic soft knee
+    pub fn gain_computer(&self, level_db: f32) -> f32 {
+        let ratio = self.ratio.max(1.0);
+        let overshoot = level_db - self.threshold_db;
+        let half_knee = self.knee_db.max(0.0) / 2.0;
+
+        if overshoot <= -half_knee {
+            0.0
+        } else if overshoot < half_knee {
+            let into_knee = overshoot + half_knee;
+            (1.0 / ratio - 1.0) * into_knee * into_knee / (2.0 * self.knee_db)
+        } else {
+            (1.0 / ratio - 1.0) * overshoot
+        }
+    }
+
+    /// Output gain in dB: manual makeup plus, when enabled, half the reduction at 0 dBFS
+    fn makeup(&self) -> f32 {
+        let auto = if self.auto_makeup { -self.gain_computer(0.0) / 2.0 } else { 0.0 };
+        self.makeup_db + auto
+    }
+}
+
+/// Feed-forward compressor with one gain curve linked across all channels.
+/// Returns the gain reduction trace for metering.
+pub fn apply_compressor(channels: &mut [Vec<f32>], sample_rate: u32, settings: CompressorSettings) -> GainReductionTrace {
+    let len = channels.iter().map(|c| c.len()).min().unwrap_or(0);
+    let attack_coeff = dynamics::time_coefficient(settings.attack_ms, sample_rate);
+    let release_coeff = dynamics::time_coefficient(settings.release_ms, sample_rate);
+    let rms_coeff = dynamics::time_coefficient(RMS_WINDOW_MS, sample_rate);
+
+    let detector = dynamics::linked_sidechain(channels, len, sample_rate, settings.sidechain_hpf, None);
+
+    let makeup = settings.makeup();
+    let mut mean_square = 0.0f32;
+    let mut reduction_db = 0.0f32;
+    let mut values_db = Vec::with_capacity(len.div_ceil(METER_BLOCK));
+    let mut block_max = 0.0f32;
+
+    for (n, &peak) in detector.iter().enumerate() {
+        let level = match settings.detection {
+            DetectionMode::Peak => peak,
+            DetectionMode::Rms => {
+                mean_square = rms_coeff * mean_square + (1.0 - rms_coeff) * peak * peak;
+                mean_square.sqrt()
+            }
+        };
+        let level_db = dynamics::level_db(level);
+
+        // Smooth the reduction in the dB domain, attacking and releasing separately
+        let target = -settings.gain_computer(level_db);
+        let coeff = if target > reduction_db { attack_coeff } else { release_coeff };
+        reduction_db = coeff * reduction_db + (1.0 - coeff) * target;
+
+        let gain = 10.0_f32.powf((makeup - reduction_db) / 20.0);
+        for channel in channels.iter_mut() {
+            channel[n] *= gain;
+        }
+
+        block_max = block_max.max(reduction_db);
+        if (n + 1) % METER_BLOCK == 0 || n + 1 == len {
+            values_db.push(block_max);
+            block_max = 0.0;
+        }
+    }
+
+    GainReductionTrace {
+        block_size: METER_BLOCK as u32,
+        values_db,
+    }
+}
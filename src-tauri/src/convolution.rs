@@ -0,0 +1,90 @@
+use std::sync::Arc;
+use realfft::{ComplexToReal, RealFftPlanner, RealToComplex};
+use rustfft::num_complex::Complex32;
+
+/// Partition length in samples; the FFT size is twice this
+const BLOCK_SIZE: usize = 4096;
+
+/// Uniformly partitioned overlap-save convolution. The impulse response is
+/// split into `BLOCK_SIZE` partitions whose spectra are computed once, so the
+/// same convolver can be run over any number of channels.
+pub struct PartitionedConvolver {
+    partitions: Vec<Vec<Complex32>>,
+    forward: Arc<dyn RealToComplex<f32>>,
+    inverse: Arc<dyn ComplexToReal<f32>>,
+}
+
+impl PartitionedConvolver {
+    pub fn new(impulse: &[f32]) -> Result<Self, Box<dyn std::error::Error>> {
+        let fft_size = 2 * BLOCK_SIZE;
+        let mut planner = RealFftPlanner::<f32>::new();
+        let forward = planner.plan_fft_forward(fft_size);
+        let inverse = planner.plan_fft_inverse(fft_size);
+
+        let mut partitions = Vec::with_capacity(impulse.len().div_ceil(BLOCK_SIZE));
+        let mut time_buffer = vec![0.0f32; fft_size];
+        for chunk in impulse.chunks(BLOCK_SIZE) {
+            time_buffer.fill(0.0);
+            time_buffer[..chunk.len()].copy_from_slice(chunk);
+            let mut spectrum = forward.make_output_vec();
+            forward.process(&mut time_buffer, &mut spectrum)?;
+            partitions.push(spectrum);
+        }
+
+        Ok(Self { partitions, forward, inverse })
+    }
+
+    /// Convolve `signal` with the impulse response and return the first `output_len` samples
+    pub fn process(&self, signal: &[f32], output_len: usize) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        let mut output = vec![0.0f32; output_len];
+        if self.partitions.is_empty() {
+            return Ok(output);
+        }
+
+        let fft_size = 2 * BLOCK_SIZE;
+        let bins = fft_size / 2 + 1;
+        let scale = 1.0 / fft_size as f32;
+
+        // Frequency-domain delay line: spectra of the most recent input blocks, newest at `head`
+        let mut delay_line = vec![vec![Complex32::new(0.0, 0.0); bins]; self.partitions.len()];
+        let mut head = 0;
+        let mut accumulator = vec![Complex32::new(0.0, 0.0); bins];
+        let mut time_buffer = vec![0.0f32; fft_size];
+
+        for block in 0..output_len.div_ceil(BLOCK_SIZE) {
+            // Sliding window over the previous and the current input block
+            let start = block * BLOCK_SIZE;
+            for (i, sample) in time_buffer.iter_mut().enumerate() {
+                *sample = (start + i)
+                    .checked_sub(BLOCK_SIZE)
+                    .and_then(|index| signal.get(index))
+                    .copied()
+                    .unwrap_or(0.0);
+            }
+
+            head = (head + self.partitions.len() - 1) % self.partitions.len();
+            self.forward.process(&mut time_buffer, &mut delay_line[head])?;
+
+            accumulator.fill(Complex32::new(0.0, 0.0));
+            for (p, partition) in self.partitions.iter().enumerate() {
+                let input = &delay_line[(head + p) % self.partitions.len()];
+                for ((acc, &x), &h) in accumulator.iter_mut().zip(input).zip(partition) {
+                    *acc += x * h;
+                }
+            }
+
+            // The inverse real FFT expects purely real DC and Nyquist bins
+            accumulator[0].im = 0.0;
+            accumulator[bins - 1].im = 0.0;
+            self.inverse.process(&mut accumulator, &mut time_buffer)?;
+
+            // The second half of the circular result is free of wrap-around
+            let end = (start + BLOCK_SIZE).min(output_len);
+            for (out, &sample) in output[start..end].iter_mut().zip(&time_buffer[BLOCK_SIZE..]) {
+                *out = sample * scale;
+            }
+        }
+
+        Ok(output)
+    }
+}
@@ -0,0 +1,111 @@
+use crate::biquad::{Biquad, BiquadCoefficients};
+use crate::dynamics;
+
+/// Feedback is kept below 1 so the echoes always die away
+const MAX_FEEDBACK: f32 = 0.95;
+/// Envelope times of the ducking detector, in milliseconds
+const DUCK_ATTACK_MS: f32 = 10.0;
+const DUCK_RELEASE_MS: f32 = 250.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct DelaySettings {
+    pub time_ms: f32,
+    /// 0..0.95, level of each repeat relative to the one before
+    pub feedback: f32,
+    /// 0 (dry) .. 1 (wet)
+    pub mix: f32,
+    /// Repeats alternate between left and right
+    pub ping_pong: bool,
+    /// High-pass corner in the feedback path, in Hz
+    pub low_cut_hz: Option<f32>,
+    /// Low-pass corner in the feedback path, in Hz
+    pub high_cut_hz: Option<f32>,
+    /// Most the wet signal is turned down while the dry signal is loud, in dB
+    pub ducking_db: f32,
+    /// Dry level in dBFS above which the wet signal is ducked
+    pub duck_threshold_db: f32,
+}
+
+/// Feedback delay with filtered repeats and optional ping-pong. The buffer
+/// keeps its length, so echoes past the end are cut off like the reverb tail.
+pub fn apply_delay(channels: &mut [Vec<f32>], sample_rate: u32, settings: DelaySettings) {
+    let len = channels.iter().map(|c| c.len()).min().unwrap_or(0);
+    if len == 0 {
+        return;
+    }
+
+    let delay = ((settings.time_ms.max(0.0) * 0.001 * sample_rate as f32).round() as usize).max(1);
+
+    let feedback = settings.feedback.clamp(0.0, MAX_FEEDBACK);
+    let ping_pong = settings.ping_pong && channels.len() == 2;
+
+    // Filters sit on each line's output, so every repeat is filtered once more than the last
+    let mut filters: Vec<Vec<Biquad>> = channels
+        .iter()
+        .map(|_| {
+            let mut chain = Vec::new();
+            if let Some(frequency) = settings.low_cut_hz {
+                chain.push(Biquad::new(BiquadCoefficients::high_pass(sample_rate as f64, frequency as f64, 0.707)));
+            }
+            if let Some(frequency) = settings.high_cut_hz {
+                chain.push(Biquad::new(BiquadCoefficients::low_pass(sample_rate as f64, frequency as f64, 0.707)));
+            }
+            chain
+        })
+        .collect();
+
+    let mut lines = vec![vec![0.0f32; delay]; channels.len()];
+    let mut wet = vec![vec![0.0f32; len]; channels.len()];
+    for n in 0..len {
+        let index = n % delay;
+        let taps: Vec<f32> = lines
+            .iter()
+            .zip(filters.iter_mut())
+            .map(|(line, chain)| chain.iter_mut().fold(line[index], |sample, filter| filter.process(sample)))
+            .collect();
+
+        if ping_pong {
+            // Mono input enters on the left; each repeat crosses to the other side
+            lines[0][index] = (channels[0][n] + channels[1][n]) * 0.5 + feedback * taps[1];
+            lines[1][index] = feedback * taps[0];
+        } else {
+            for (c, line) in lines.iter_mut().enumerate() {
+                line[index] = channels[c][n] + feedback * taps[c];
+            }
+        }
+
+        for (c, tap) in taps.into_iter().enumerate() {
+            wet[c][n] = tap;
+        }
+    }
+
+    let ducking = duck_gains(channels, len, sample_rate, &settings);
+    let mix = settings.mix.clamp(0.0, 1.0);
+    for (channel, wet) in channels.iter_mut().zip(&wet) {
+        for ((sample, &echo), &duck) in channel.iter_mut().zip(wet).zip(&ducking) {
+            *sample = *sample * (1.0 - mix) + echo * duck * mix;
+        }
+    }
+}
+
+/// Wet gain per sample: reduced by however far the dry level is over the threshold, up to `ducking_db`
+fn duck_gains(channels: &[Vec<f32>], len: usize, sample_rate: u32, settings: &DelaySettings) -> Vec<f32> {
+    if settings.ducking_db <= 0.0 {
+        return vec![1.0; len];
+    }
+
+    let attack_coeff = dynamics::time_coefficient(DUCK_ATTACK_MS, sample_rate);
+    let release_coeff = dynamics::time_coefficient(DUCK_RELEASE_MS, sample_rate);
+    let mut envelope = 0.0f32;
+
+    dynamics::linked_sidechain(channels, len, sample_rate, None, None)
+        .into_iter()
+        .map(|peak| {
+            let coeff = if peak > envelope { attack_coeff } else { release_coeff };
+            envelope = coeff * envelope + (1.0 - coeff) * peak;
+
+            let reduction = (dynamics::level_db(envelope) - settings.duck_threshold_db).clamp(0.0, settings.ducking_db);
+            10.0_f32.powf(-reduction / 20.0)
+        })
+        .collect()
+}
@@ -0,0 +1,42 @@
+use crate::biquad::{Biquad, BiquadCoefficients};
+
+/// Floor for level conversion to dB
+pub const MIN_LEVEL_DB: f32 = -120.0;
+
+/// One-pole smoothing coefficient for a time constant in milliseconds
+pub fn time_coefficient(ms: f32, sample_rate: u32) -> f32 {
+    (-1.0 / (ms.max(0.01) * 0.001 * sample_rate as f32)).exp()
+}
+
+/// Level in dB, floored at `MIN_LEVEL_DB`
+pub fn level_db(level: f32) -> f32 {
+    (20.0 * level.log10()).max(MIN_LEVEL_DB)
+}
+
+/// Detector input shared by the dynamics stages: each channel optionally
+/// band-limited, linked by taking the loudest channel at every sample
+pub fn linked_sidechain(
+    channels: &[Vec<f32>],
+    len: usize,
+    sample_rate: u32,
+    high_pass: Option<f32>,
+    low_pass: Option<f32>,
+) -> Vec<f32> {
+    let sample_rate = sample_rate as f64;
+    let mut detector = vec![0.0f32; len];
+
+    for channel in channels {
+        let mut sidechain = channel[..len].to_vec();
+        if let Some(frequency) = high_pass {
+            Biquad::new(BiquadCoefficients::high_pass(sample_rate, frequency as f64, 0.707)).process_buffer(&mut sidechain);
+        }
+        if let Some(frequency) = low_pass {
+            Biquad::new(BiquadCoefficients::low_pass(sample_rate, frequency as f64, 0.707)).process_buffer(&mut sidechain);
+        }
+        for (level, s) in detector.iter_mut().zip(&sidechain) {
+            *level = level.max(s.abs());
+        }
+    }
+
+    detector
+}
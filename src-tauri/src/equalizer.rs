@@ -0,0 +1,125 @@
+use realfft::RealFftPlanner;
+use rustfft::num_complex::Complex32;
+
+use crate::audio_types::{AdvancedAudioEffects, EqBand, EqBandType};
+use crate::biquad::{Biquad, BiquadCoefficients};
+use crate::convolution::PartitionedConvolver;
+
+/// Q of the five legacy bands
+const LEGACY_Q: f32 = 0.7;
+
+/// Every EQ band in the effects payload, in processing order: the legacy
+/// five-band controls (as peaking bands) followed by the parametric bands
+pub fn bands_from_effects(effects: &AdvancedAudioEffects) -> Vec<EqBand> {
+    let legacy = [
+        (60.0, effects.eq_low),
+        (250.0, effects.eq_low_mid),
+        (1000.0, effects.eq_mid),
+        (4000.0, effects.eq_high_mid),
+        (16000.0, effects.eq_high),
+    ];
+
+    let mut bands: Vec<EqBand> = legacy
+        .iter()
+        .filter_map(|&(frequency, value)| {
+            // Normalized gain (0-1) to dB (-20 to +20)
+            let gain_db = (value? - 0.5) * 40.0;
+            (gain_db.abs() >= 0.1).then_some(EqBand {
+                band_type: EqBandType::Peak,
+                frequency,
+                gain_db,
+                q: LEGACY_Q,
+            })
+        })
+        .collect();
+
+    bands.extend(effects.eq_bands.iter().flatten().cloned());
+    bands
+}
+
+/// Biquad realizing one band at the given sample rate
+pub fn band_coefficients(band: &EqBand, sample_rate: u32) -> BiquadCoefficients {
+    let sample_rate = sample_rate as f64;
+    let frequency = band.frequency as f64;
+    let gain_db = band.gain_db as f64;
+    let q = band.q as f64;
+
+    match band.band_type {
+        EqBandType::Peak => BiquadCoefficients::peak(sample_rate, frequency, gain_db, q),
+        EqBandType::LowShelf => BiquadCoefficients::low_shelf(sample_rate, frequency, gain_db, q),
+        EqBandType::HighShelf => BiquadCoefficients::high_shelf(sample_rate, frequency, gain_db, q),
+        EqBandType::LowPass => BiquadCoefficients::low_pass(sample_rate, frequency, q),
+        EqBandType::HighPass => BiquadCoefficients::high_pass(sample_rate, frequency, q),
+        EqBandType::Notch => BiquadCoefficients::notch(sample_rate, frequency, q),
+        EqBandType::BandPass => BiquadCoefficients::band_pass(sample_rate, frequency, q),
+        EqBandType::Tilt => BiquadCoefficients::tilt(sample_rate, frequency, gain_db),
+    }
+}
+
+/// Run every channel through all bands in series
+pub fn apply_bands(channels: &mut [Vec<f32>], bands: &[EqBand], sample_rate: u32) {
+    let coefficients: Vec<BiquadCoefficients> = bands
+        .iter()
+        .map(|band| band_coefficients(band, sample_rate))
+        .collect();
+
+    for channel in channels.iter_mut() {
+        for &band in &coefficients {
+            Biquad::new(band).process_buffer(channel);
+        }
+    }
+}
+
+/// Run every channel through a linear-phase FIR with the same magnitude
+/// response as the biquad cascade. The FIR delay is removed from the output;
+/// returns that latency in samples.
+pub fn apply_linear_phase(
+    channels: &mut [Vec<f32>],
+    bands: &[EqBand],
+    sample_rate: u32,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let fir = linear_phase_fir(bands, sample_rate)?;
+    let latency = fir.len() / 2;
+    let convolver = PartitionedConvolver::new(&fir)?;
+
+    for channel in channels.iter_mut() {
+        let mut filtered = convolver.process(channel, channel.len() + latency)?;
+        filtered.drain(..latency);
+        *channel = filtered;
+    }
+
+    Ok(latency)
+}
+
+/// Zero-phase design of the cascade's magnitude response, delayed by half its length
+fn linear_phase_fir(bands: &[EqBand], sample_rate: u32) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+    // Long enough for ~3 Hz resolution, so low shelves and high-passes keep their shape
+    let length = if sample_rate > 48000 { 32768 } else { 16384 };
+    let coefficients: Vec<BiquadCoefficients> = bands
+        .iter()
+        .map(|band| band_coefficients(band, sample_rate))
+        .collect();
+
+    let mut planner = RealFftPlanner::<f32>::new();
+    let inverse = planner.plan_fft_inverse(length);
+    let mut spectrum = inverse.make_input_vec();
+    for (bin, value) in spectrum.iter_mut().enumerate() {
+        let frequency = bin as f64 * sample_rate as f64 / length as f64;
+        let magnitude: f64 = coefficients
+            .iter()
+            .map(|band| band.magnitude(sample_rate as f64, frequency))
+            .product();
+        *value = Complex32::new(magnitude as f32 / length as f32, 0.0);
+    }
+
+    let mut impulse = inverse.make_output_vec();
+    inverse.process(&mut spectrum, &mut impulse)?;
+
+    // Center the symmetric impulse and window it to limit truncation ripple
+    let fir = apodize::hanning_iter(length)
+        .enumerate()
+        .map(|(n, window)| impulse[(n + length / 2) % length] * window as f32)
+        .collect();
+
+    Ok(fir)
+}
@@ -0,0 +1,137 @@
+/// Comb and all-pass delays (samples at 44.1 kHz) from Jezar's Freeverb
+const COMB_TUNING: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+const ALLPASS_TUNING: [usize; 4] = [556, 441, 341, 225];
+/// Extra delay of the right channel's filters, decorrelating the two sides
+const STEREO_SPREAD: usize = 23;
+const FIXED_GAIN: f32 = 0.015;
+const SCALE_ROOM: f32 = 0.28;
+const OFFSET_ROOM: f32 = 0.7;
+const SCALE_DAMP: f32 = 0.4;
+const ALLPASS_FEEDBACK: f32 = 0.5;
+/// Makes a wet level of 1.0 roughly as loud as the dry signal
+const WET_SCALE: f32 = 3.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct FreeverbSettings {
+    /// 0..1, longer decay as it grows
+    pub room_size: f32,
+    /// 0..1, how fast high frequencies die away in the tail
+    pub damping: f32,
+    /// Gap before the tail starts, in milliseconds
+    pub pre_delay_ms: f32,
+    /// 0..1 from a mono tail to fully decorrelated sides
+    pub width: f32,
+}
+
+/// Lowpass-feedback comb filter
+struct Comb {
+    buffer: Vec<f32>,
+    index: usize,
+    filter_store: f32,
+}
+
+impl Comb {
+    fn new(length: usize) -> Self {
+        Self { buffer: vec![0.0; length.max(1)], index: 0, filter_store: 0.0 }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damp: f32) -> f32 {
+        let output = self.buffer[self.index];
+        self.filter_store = output * (1.0 - damp) + self.filter_store * damp;
+        self.buffer[self.index] = input + self.filter_store * feedback;
+        self.index = (self.index + 1) % self.buffer.len();
+        output
+    }
+}
+
+/// Schroeder all-pass diffuser
+struct Allpass {
+    buffer: Vec<f32>,
+    index: usize,
+}
+
+impl Allpass {
+    fn new(length: usize) -> Self {
+        Self { buffer: vec![0.0; length.max(1)], index: 0 }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let buffered = self.buffer[self.index];
+        self.buffer[self.index] = input + buffered * ALLPASS_FEEDBACK;
+        self.index = (self.index + 1) % self.buffer.len();
+        buffered - input
+    }
+}
+
+/// One side of the reverb: eight parallel combs into four series all-passes
+struct Tank {
+    combs: Vec<Comb>,
+    allpasses: Vec<Allpass>,
+}
+
+impl Tank {
+    fn new(scale: f64, spread: usize) -> Self {
+        let length = |tuning: usize| ((tuning + spread) as f64 * scale).round() as usize;
+        Self {
+            combs: COMB_TUNING.iter().map(|&t| Comb::new(length(t))).collect(),
+            allpasses: ALLPASS_TUNING.iter().map(|&t| Allpass::new(length(t))).collect(),
+        }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damp: f32) -> f32 {
+        let mut output: f32 = self.combs.iter_mut().map(|comb| comb.process(input, feedback, damp)).sum();
+        for allpass in &mut self.allpasses {
+            output = allpass.process(output);
+        }
+        output
+    }
+}
+
+/// Freeverb-style stereo algorithmic reverb producing the wet signal only
+pub struct Freeverb {
+    left: Tank,
+    right: Tank,
+    feedback: f32,
+    damp: f32,
+    pre_delay: Vec<f32>,
+    pre_delay_index: usize,
+    direct_gain: f32,
+    cross_gain: f32,
+}
+
+impl Freeverb {
+    pub fn new(sample_rate: u32, settings: FreeverbSettings) -> Self {
+        // The tunings are in samples at 44.1 kHz; keep the same times at other rates
+        let scale = sample_rate as f64 / 44100.0;
+        let pre_delay_len = (settings.pre_delay_ms.max(0.0) * 0.001 * sample_rate as f32) as usize;
+        let width = settings.width.clamp(0.0, 1.0);
+
+        Self {
+            left: Tank::new(scale, 0),
+            right: Tank::new(scale, STEREO_SPREAD),
+            feedback: settings.room_size.clamp(0.0, 1.0) * SCALE_ROOM + OFFSET_ROOM,
+            damp: settings.damping.clamp(0.0, 1.0) * SCALE_DAMP,
+            pre_delay: vec![0.0; pre_delay_len],
+            pre_delay_index: 0,
+            direct_gain: WET_SCALE * (width / 2.0 + 0.5),
+            cross_gain: WET_SCALE * (1.0 - width) / 2.0,
+        }
+    }
+
+    /// Wet output for one stereo input frame
+    pub fn process(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let mut input = (left + right) * FIXED_GAIN;
+        if !self.pre_delay.is_empty() {
+            input = std::mem::replace(&mut self.pre_delay[self.pre_delay_index], input);
+            self.pre_delay_index = (self.pre_delay_index + 1) % self.pre_delay.len();
+        }
+
+        let out_left = self.left.process(input, self.feedback, self.damp);
+        let out_right = self.right.process(input, self.feedback, self.damp);
+
+        (
+            out_left * self.direct_gain + out_right * self.cross_gain,
+            out_right * self.direct_gain + out_left * self.cross_gain,
+        )
+    }
+}
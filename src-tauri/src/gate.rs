@@ -0,0 +1,83 @@
+use crate::dynamics;
+
+/// Decay time of the peak detector in milliseconds; keeps the envelope from
+/// dropping to zero between waveform peaks
+const DETECTOR_RELEASE_MS: f32 = 10.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct GateSettings {
+    pub threshold_db: f32,
+    /// Largest attenuation in dB; a gate that never closes fully
+    pub range_db: f32,
+    /// Expansion ratio below the threshold; large ratios behave like a gate
+    pub ratio: f32,
+    pub attack_ms: f32,
+    pub hold_ms: f32,
+    pub release_ms: f32,
+    /// How far ahead the detector looks, so the gate is open when a transient arrives
+    pub lookahead_ms: f32,
+    /// Sidechain high-pass corner in Hz
+    pub sidechain_hpf: Option<f32>,
+    /// Sidechain low-pass corner in Hz
+    pub sidechain_lpf: Option<f32>,
+}
+
+impl GateSettings {
+    /// Static curve: attenuation in dB (zero or positive) for a detector level in dB
+    fn attenuation(&self, level_db: f32) -> f32 {
+        let below = self.threshold_db - level_db;
+        if below <= 0.0 {
+            0.0
+        } else {
+            (below * (self.ratio.max(1.0) - 1.0)).min(self.range_db.max(0.0))
+        }
+    }
+}
+
+/// Downward expander / noise gate with one gain curve linked across all channels
+pub fn apply_gate(channels: &mut [Vec<f32>], sample_rate: u32, settings: GateSettings) {
+    let len = channels.iter().map(|c| c.len()).min().unwrap_or(0);
+    if len == 0 {
+        return;
+    }
+
+    let attack_coeff = dynamics::time_coefficient(settings.attack_ms, sample_rate);
+    let release_coeff = dynamics::time_coefficient(settings.release_ms, sample_rate);
+    let detector_coeff = dynamics::time_coefficient(DETECTOR_RELEASE_MS, sample_rate);
+    let hold_samples = (settings.hold_ms.max(0.0) * 0.001 * sample_rate as f32) as usize;
+    let lookahead = (settings.lookahead_ms.max(0.0) * 0.001 * sample_rate as f32) as usize;
+
+    let detector = dynamics::linked_sidechain(channels, len, sample_rate, settings.sidechain_hpf, settings.sidechain_lpf);
+
+    let mut envelope = 0.0f32;
+    let mut hold_left = 0usize;
+    let mut attenuation_db = 0.0f32;
+    let gains: Vec<f32> = detector
+        .iter()
+        .map(|&peak| {
+            envelope = peak.max(detector_coeff * envelope);
+            let level_db = dynamics::level_db(envelope);
+
+            // Stay open for the hold time after the signal last crossed the threshold
+            let mut target = settings.attenuation(level_db);
+            if target == 0.0 {
+                hold_left = hold_samples;
+            } else if hold_left > 0 {
+                hold_left -= 1;
+                target = 0.0;
+            }
+
+            // Opening is the attack, closing the release
+            let coeff = if target < attenuation_db { attack_coeff } else { release_coeff };
+            attenuation_db = coeff * attenuation_db + (1.0 - coeff) * target;
+            10.0_f32.powf(-attenuation_db / 20.0)
+        })
+        .collect();
+
+    // Offline lookahead: each sample takes the gain computed `lookahead` samples later
+    for channel in channels.iter_mut() {
+        for (n, sample) in channel[..len].iter_mut().enumerate() {
+            *sample *= gains[(n + lookahead).min(len - 1)];
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::VecDeque;
+
+use crate::oversampler::Oversampler;
+
+/// Oversampling used to find inter-sample peaks (as in ITU-R BS.1770-4)
+const TRUE_PEAK_OVERSAMPLING: usize = 4;
+
+#[derive(Debug, Clone, Copy)]
+pub struct LimiterSettings {
+    /// Output ceiling in dBTP
+    pub threshold_db: f32,
+    /// How far ahead the gain starts to ramp down before a peak, in milliseconds
+    pub lookahead_ms: f32,
+    /// Time for the gain to recover after a peak, in seconds
+    pub release: f32,
+}
+
+/// Brickwall limiter with lookahead and true-peak detection. One gain curve is
+/// shared by all channels so the stereo image does not move. The whole buffer
+/// is known up front, so the lookahead adds no latency to the output.
+/// Returns the largest gain reduction applied, in dB.
+pub fn apply_limiter(channels: &mut [Vec<f32>], sample_rate: u32, settings: LimiterSettings) -> f32 {
+    let len = channels.iter().map(|c| c.len()).min().unwrap_or(0);
+    if len == 0 {
+        return 0.0;
+    }
+
+    let ceiling = 10.0_f32.powf(settings.threshold_db / 20.0);
+    let lookahead = ((settings.lookahead_ms.max(0.0) * 0.001 * sample_rate as f32) as usize).max(1);
+    let release_coeff = (-1.0 / (settings.release.max(0.001) * sample_rate as f32)).exp();
+
+    // Linked detection: the loudest true peak across channels at every sample
+    let oversampler = Oversampler::new(TRUE_PEAK_OVERSAMPLING);
+    let mut peak = vec![0.0f32; len];
+    for channel in channels.iter() {
+        let upsampled = oversampler.upsample(&channel[..len]);
+        for (p, block) in peak.iter_mut().zip(upsampled.chunks(TRUE_PEAK_OVERSAMPLING)) {
+            *p = block.iter().fold(*p, |p, s| p.max(s.abs()));
+        }
+    }
+
+    let required: Vec<f32> = peak
+        .iter()
+        .map(|&p| if p > ceiling { ceiling / p } else { 1.0 })
+        .collect();
+
+    // Hold the lowest gain needed anywhere in the next `lookahead` samples...
+    let held = forward_minimum(&required, lookahead);
+
+    // ...and ramp into it with a moving average over the same window, which
+    // still reaches the required gain by the time each peak arrives
+    let mut smoothed = vec![0.0f32; len];
+    let mut sum = 0.0f64;
+    for n in 0..len {
+        sum += held[n] as f64;
+        if n >= lookahead {
+            sum -= held[n - lookahead] as f64;
+        }
+        let count = (n + 1).min(lookahead);
+        smoothed[n] = (sum / count as f64) as f32;
+    }
+
+    // Instant on the way down, exponential release on the way up
+    let mut gain = 1.0f32;
+    let mut min_gain = 1.0f32;
+    for n in 0..len {
+        let target = smoothed[n].min(required[n]);
+        gain = if target < gain {
+            target
+        } else {
+            release_coeff * gain + (1.0 - release_coeff) * target
+        };
+        min_gain = min_gain.min(gain);
+
+        for channel in channels.iter_mut() {
+            channel[n] *= gain;
+        }
+    }
+
+    -20.0 * min_gain.log10()
+}
+
+/// Minimum of `values[n..n + window]` for every n, with a monotonic queue
+fn forward_minimum(values: &[f32], window: usize) -> Vec<f32> {
+    let mut result = vec![1.0f32; values.len()];
+    let mut queue: VecDeque<usize> = VecDeque::new();
+
+    for i in (0..values.len()).rev() {
+        while queue.back().is_some_and(|&j| values[j] >= values[i]) {
+            queue.pop_back();
+        }
+        queue.push_back(i);
+        while queue.front().is_some_and(|&j| j >= i + window) {
+            queue.pop_front();
+        }
+        result[i] = values[queue[0]];
+    }
+
+    result
+}
@@ -0,0 +1,138 @@
+use std::f32::consts::{LN_2, PI};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::audio_types::SaturationCurve;
+use crate::biquad::{Biquad, BiquadCoefficients};
+use crate::modulation::hermite;
+use crate::saturation::{self, SaturationSettings};
+
+/// Share of crackle events that are louder, duller pops
+const POP_CHANCE: f32 = 0.05;
+const POP_GAIN: f32 = 4.0;
+/// Decay times of a crackle and of a pop, in milliseconds
+const CRACKLE_DECAY_MS: f32 = 0.2;
+const POP_DECAY_MS: f32 = 2.0;
+/// Oversampling used for the tape saturation
+const TAPE_OVERSAMPLING: usize = 4;
+
+#[derive(Debug, Clone, Copy)]
+pub struct LofiSettings {
+    pub seed: u64,
+    /// Crackle events per second
+    pub crackle_density: f32,
+    /// Peak crackle level in dBFS
+    pub crackle_level_db: f32,
+    /// Hiss level in dBFS; no hiss when unset
+    pub hiss_level_db: Option<f32>,
+    pub wow_rate_hz: f32,
+    /// Largest pitch deviation of the wow in cents
+    pub wow_depth_cents: f32,
+    pub flutter_rate_hz: f32,
+    /// Largest pitch deviation of the flutter in cents
+    pub flutter_depth_cents: f32,
+    /// Gain into the tape curve in dB; the curve is skipped at 0
+    pub tape_drive_db: f32,
+    pub low_cut_hz: f32,
+    pub high_cut_hz: f32,
+}
+
+/// Vinyl/tape simulation: pitch wobble, tape saturation, crackle and hiss,
+/// then a band-limit filter. The same seed always renders the same noise and wobble.
+pub fn apply_lofi(
+    channels: &mut [Vec<f32>],
+    sample_rate: u32,
+    settings: LofiSettings,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut rng = StdRng::seed_from_u64(settings.seed);
+
+    apply_wobble(channels, sample_rate, &settings, &mut rng);
+
+    if settings.tape_drive_db > 0.0 {
+        saturation::apply_saturation(
+            channels,
+            sample_rate,
+            SaturationSettings {
+                curve: SaturationCurve::Tape,
+                drive_db: settings.tape_drive_db,
+                output_db: 0.0,
+                mix: 1.0,
+                oversampling: TAPE_OVERSAMPLING,
+            },
+        )?;
+    }
+
+    let sr = sample_rate as f32;
+    let crackle_chance = settings.crackle_density.max(0.0) / sr;
+    let crackle_level = 10.0_f32.powf(settings.crackle_level_db / 20.0);
+    let hiss_level = settings.hiss_level_db.map(|db| 10.0_f32.powf(db / 20.0)).unwrap_or(0.0);
+    let crackle_decay = (-1.0 / (CRACKLE_DECAY_MS * 0.001 * sr)).exp();
+    let pop_decay = (-1.0 / (POP_DECAY_MS * 0.001 * sr)).exp();
+
+    for channel in channels.iter_mut() {
+        // Each event excites a one-pole decay; crackles and pops ring out separately
+        let mut crackle = 0.0f32;
+        let mut pop = 0.0f32;
+        for sample in channel.iter_mut() {
+            if rng.gen::<f32>() < crackle_chance {
+                let amplitude = crackle_level * rng.gen_range(0.2..1.0) * if rng.gen::<bool>() { 1.0 } else { -1.0 };
+                if rng.gen::<f32>() < POP_CHANCE {
+                    pop += amplitude * POP_GAIN;
+                } else {
+                    crackle += amplitude;
+                }
+            }
+            let hiss = if hiss_level > 0.0 { hiss_level * (rng.gen::<f32>() * 2.0 - 1.0) } else { 0.0 };
+
+            *sample += crackle + pop + hiss;
+            crackle *= crackle_decay;
+            pop *= pop_decay;
+        }
+    }
+
+    for channel in channels.iter_mut() {
+        Biquad::new(BiquadCoefficients::high_pass(sr as f64, settings.low_cut_hz as f64, 0.707)).process_buffer(channel);
+        Biquad::new(BiquadCoefficients::low_pass(sr as f64, settings.high_cut_hz.min(sr * 0.45) as f64, 0.707))
+            .process_buffer(channel);
+    }
+
+    Ok(())
+}
+
+/// Wow and flutter: every channel is read at a time offset that swings
+/// sinusoidally, which bends the pitch by up to the given depth in cents.
+/// Offline, so the offset can point ahead as well as behind and adds no latency.
+fn apply_wobble(channels: &mut [Vec<f32>], sample_rate: u32, settings: &LofiSettings, rng: &mut StdRng) {
+    let sr = sample_rate as f32;
+    // A delay swing of A seconds at f Hz shifts pitch by up to 2πfA, i.e. 1200/ln2 · 2πfA cents
+    let swing = |rate: f32, cents: f32| {
+        if rate > 0.0 { cents.max(0.0) * LN_2 / 1200.0 / (2.0 * PI * rate) * sr } else { 0.0 }
+    };
+    let wow_swing = swing(settings.wow_rate_hz, settings.wow_depth_cents);
+    let flutter_swing = swing(settings.flutter_rate_hz, settings.flutter_depth_cents);
+    if wow_swing == 0.0 && flutter_swing == 0.0 {
+        return;
+    }
+
+    // Both sides share the transport, so one random starting phase per LFO
+    // Phases in f64, so long songs do not lose precision
+    let wow_phase = rng.gen_range(0.0..2.0 * PI) as f64;
+    let flutter_phase = rng.gen_range(0.0..2.0 * PI) as f64;
+    let wow_step = 2.0 * std::f64::consts::PI * settings.wow_rate_hz as f64 / sr as f64;
+    let flutter_step = 2.0 * std::f64::consts::PI * settings.flutter_rate_hz as f64 / sr as f64;
+
+    for channel in channels.iter_mut() {
+        let input = channel.clone();
+        let last = input.len() as isize - 1;
+        let at = |i: isize| input[i.clamp(0, last.max(0)) as usize];
+
+        for (n, sample) in channel.iter_mut().enumerate() {
+            let offset = wow_swing * (wow_phase + wow_step * n as f64).sin() as f32
+                + flutter_swing * (flutter_phase + flutter_step * n as f64).sin() as f32;
+            // Whole samples stay integer; only the small offset carries the fraction
+            let index = n as isize + offset.floor() as isize;
+            let fraction = offset - offset.floor();
+            *sample = hermite(at(index - 1), at(index), at(index + 1), at(index + 2), fraction);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::f64::consts::PI;
+
+use crate::audio_types::AudioBuffer;
+use crate::biquad::{Biquad, BiquadCoefficients};
+
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+const RELATIVE_GATE_LU: f64 = -10.0;
+
+/// Integrated loudness in LUFS as defined by ITU-R BS.1770-4.
+/// Returns `None` when the whole programme sits below the absolute gate.
+pub fn integrated_loudness(audio_buffer: &AudioBuffer) -> Option<f32> {
+    let sample_rate = audio_buffer.sample_rate as f64;
+    let block_len = (0.4 * sample_rate) as usize;
+    let hop = (0.1 * sample_rate) as usize;
+    let frames = audio_buffer.channels.first().map_or(0, |c| c.len());
+
+    if block_len == 0 || hop == 0 || frames < block_len {
+        return None;
+    }
+
+    // K-weighted mean square of every 400 ms block (75% overlap), summed over channels
+    let block_count = (frames - block_len) / hop + 1;
+    let mut block_power = vec![0.0f64; block_count];
+
+    for channel in &audio_buffer.channels {
+        let mut weighted = channel.clone();
+        for coefficients in k_weighting(sample_rate) {
+            Biquad::new(coefficients).process_buffer(&mut weighted);
+        }
+
+        for (block, power) in block_power.iter_mut().enumerate() {
+            let start = block * hop;
+            let sum: f64 = weighted[start..start + block_len]
+                .iter()
+                .map(|&s| (s as f64) * (s as f64))
+                .sum();
+            *power += sum / block_len as f64;
+        }
+    }
+
+    let loudness = |power: f64| -0.691 + 10.0 * power.log10();
+
+    let gated_mean = |threshold: f64| {
+        let gated: Vec<f64> = block_power
+            .iter()
+            .copied()
+            .filter(|&p| p > 0.0 && loudness(p) > threshold)
+            .collect();
+        if gated.is_empty() {
+            None
+        } else {
+            Some(gated.iter().sum::<f64>() / gated.len() as f64)
+        }
+    };
+
+    let absolute_mean = gated_mean(ABSOLUTE_GATE_LUFS)?;
+    let relative_threshold = loudness(absolute_mean) + RELATIVE_GATE_LU;
+    let relative_mean = gated_mean(relative_threshold.max(ABSOLUTE_GATE_LUFS))?;
+
+    Some(loudness(relative_mean) as f32)
+}
+
+/// Scale the buffer so its integrated loudness hits `target_lufs`
+pub fn normalize_loudness(audio_buffer: &mut AudioBuffer, target_lufs: f32) {
+    let Some(measured) = integrated_loudness(audio_buffer) else {
+        return; // Silence: nothing to normalize
+    };
+
+    let gain = 10.0_f32.powf((target_lufs - measured) / 20.0);
+    for channel in &mut audio_buffer.channels {
+        for sample in channel.iter_mut() {
+            *sample *= gain;
+        }
+    }
+}
+
+/// Pre-filter (high shelf) and RLB high-pass of the K-weighting curve at any sample rate
+fn k_weighting(sample_rate: f64) -> [BiquadCoefficients; 2] {
+    // High shelf modelling the acoustic effect of the head
+    let f0 = 1681.974450955533;
+    let gain_db = 3.999843853973347;
+    let q = 0.7071752369554196;
+    let k = (PI * f0 / sample_rate).tan();
+    let vh = 10.0_f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let shelf = BiquadCoefficients::from_raw(
+        vh + vb * k / q + k * k,
+        2.0 * (k * k - vh),
+        vh - vb * k / q + k * k,
+        1.0 + k / q + k * k,
+        2.0 * (k * k - 1.0),
+        1.0 - k / q + k * k,
+    );
+
+    // Revised low-frequency B-curve high-pass (numerator left unnormalized, as in the reference)
+    let f0 = 38.13547087602444;
+    let q = 0.5003270373238773;
+    let k = (PI * f0 / sample_rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = BiquadCoefficients {
+        b0: 1.0,
+        b1: -2.0,
+        b2: 1.0,
+        a1: 2.0 * (k * k - 1.0) / a0,
+        a2: (1.0 - k / q + k * k) / a0,
+    };
+
+    [shelf, high_pass]
+}
@@ -1,7 +1,7 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use tauri::{AppHandle, Manager, Menu, MenuItem, Submenu, WindowEvent};
+use tauri::{AppHandle, Emitter, Manager, Menu, MenuItem, Submenu, WindowEvent};
 use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
 use serde_json;
 use std::path::PathBuf;
@@ -9,10 +9,37 @@ use std::path::PathBuf;
 mod audio_types;
 mod audio_loader;
 mod audio_processor;
+mod audio_exporter;
+mod biquad;
+mod compressor;
+mod convolution;
+mod delay;
+mod dynamics;
+mod equalizer;
+mod freeverb;
+mod gate;
+mod limiter;
+mod lofi;
+mod loudness;
+mod modulation;
+mod multiband;
+mod neural_separation;
+mod oversampler;
+mod phase_vocoder;
+mod recipe;
+mod resampler;
+mod saturation;
+mod stereo;
+mod vocal_separation;
+mod wav_chunks;
 
-use audio_types::{AudioBuffer, AdvancedAudioEffects};
+use audio_types::{
+    AudioBuffer, AdvancedAudioEffects, AudioMarker, ExportTarget, LoadedAudio, ProcessedAudio, ProcessingProgress,
+    ProcessingRecipe, StemSeparationSettings, StemSet,
+};
 use audio_loader::AudioLoader;
 use audio_processor::AudioProcessor;
+use audio_exporter::AudioExporter;
 
 // Learn more about Tauri commands at https://tauri.app/v1/guides/features/command
 #[tauri::command]
@@ -55,11 +82,17 @@ async fn save_file_dialog(app: AppHandle, default_name: Option<String>) -> Resul
 }
 
 #[tauri::command]
-async fn load_audio_file(file_path: String) -> Result<AudioBuffer, String> {
-    AudioLoader::load_audio_file(&file_path)
+async fn load_audio_file(file_path: String) -> Result<LoadedAudio, String> {
+    AudioLoader::open(&file_path)
         .map_err(|e| format!("Failed to load audio file: {}", e))
 }
 
+#[tauri::command]
+async fn load_audio_markers(file_path: String) -> Result<Vec<AudioMarker>, String> {
+    AudioLoader::load_markers(&file_path)
+        .map_err(|e| format!("Failed to read audio markers: {}", e))
+}
+
 #[tauri::command]
 async fn process_audio_with_effects(
     audio_buffer: AudioBuffer,
@@ -69,13 +102,95 @@ async fn process_audio_with_effects(
         .map_err(|e| format!("Failed to process audio: {}", e))
 }
 
+#[tauri::command]
+async fn process_audio_with_report(
+    audio_buffer: AudioBuffer,
+    effects: AdvancedAudioEffects,
+) -> Result<ProcessedAudio, String> {
+    AudioProcessor::process_audio_with_report(audio_buffer, &effects)
+        .map(|(audio_buffer, report)| ProcessedAudio { audio_buffer, report })
+        .map_err(|e| format!("Failed to process audio: {}", e))
+}
+
 #[tauri::command]
 async fn save_audio_file(
     audio_buffer: AudioBuffer,
     output_path: String,
+    markers: Option<Vec<AudioMarker>>,
+    effects: Option<AdvancedAudioEffects>,
+    source_path: Option<String>,
 ) -> Result<(), String> {
-    AudioLoader::save_as_wav(&audio_buffer, &output_path)
-        .map_err(|e| format!("Failed to save audio file: {}", e))
+    let processing_recipe = effects
+        .map(|effects| recipe::build_recipe(&effects, source_path.as_deref()))
+        .transpose()
+        .map_err(|e| format!("Failed to build processing recipe: {}", e))?;
+
+    AudioLoader::save_as_wav(
+        &audio_buffer,
+        &output_path,
+        32,
+        &markers.unwrap_or_default(),
+        processing_recipe.as_ref(),
+    )
+    .map_err(|e| format!("Failed to save audio file: {}", e))
+}
+
+#[tauri::command]
+async fn export_audio(
+    audio_buffer: AudioBuffer,
+    effects: AdvancedAudioEffects,
+    targets: Vec<ExportTarget>,
+    markers: Option<Vec<AudioMarker>>,
+    source_path: Option<String>,
+) -> Result<Vec<String>, String> {
+    AudioExporter::export(
+        audio_buffer,
+        &effects,
+        &targets,
+        &markers.unwrap_or_default(),
+        source_path.as_deref(),
+    )
+    .map_err(|e| format!("Failed to export audio: {}", e))
+}
+
+#[tauri::command]
+async fn read_audio_recipe(file_path: String) -> Result<Option<ProcessingRecipe>, String> {
+    recipe::read_recipe(&file_path)
+        .map_err(|e| format!("Failed to read processing recipe: {}", e))
+}
+
+#[tauri::command]
+async fn apply_audio_recipe(
+    recipe_file_path: String,
+    source_path: String,
+) -> Result<AudioBuffer, String> {
+    let processing_recipe = recipe::read_recipe(&recipe_file_path)
+        .map_err(|e| format!("Failed to read processing recipe: {}", e))?
+        .ok_or_else(|| format!("No processing recipe found in {}", recipe_file_path))?;
+
+    let audio_buffer = AudioLoader::load_audio_file(&source_path)
+        .map_err(|e| format!("Failed to load audio file: {}", e))?;
+
+    AudioProcessor::process_audio(audio_buffer, &processing_recipe.effects)
+        .map_err(|e| format!("Failed to process audio: {}", e))
+}
+
+#[tauri::command]
+async fn separate_stems(
+    app: AppHandle,
+    audio_buffer: AudioBuffer,
+    settings: StemSeparationSettings,
+) -> Result<StemSet, String> {
+    neural_separation::separate_stems(&audio_buffer, &settings, |percentage| {
+        let _ = app.emit(
+            "stem-separation-progress",
+            ProcessingProgress {
+                percentage,
+                stage: "Separating stems".to_string(),
+            },
+        );
+    })
+    .map_err(|e| format!("Failed to separate stems: {}", e))
 }
 
 #[tauri::command]
@@ -218,8 +333,14 @@ fn main() {
             open_file_dialog, 
             save_file_dialog, 
             load_audio_file, 
+            load_audio_markers,
             process_audio_with_effects, 
+            process_audio_with_report,
             save_audio_file,
+            export_audio,
+            read_audio_recipe,
+            apply_audio_recipe,
+            separate_stems,
             get_audio_analysis
         ])
         .run(tauri::generate_context!())
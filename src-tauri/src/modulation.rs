@@ -0,0 +1,127 @@
+use std::f32::consts::PI;
+
+/// Centre delay and widest sweep of the chorus, in milliseconds
+const CHORUS_DELAY_MS: f32 = 20.0;
+const CHORUS_SWEEP_MS: f32 = 10.0;
+/// Shortest delay and widest sweep of the flanger, in milliseconds
+const FLANGER_DELAY_MS: f32 = 0.5;
+const FLANGER_SWEEP_MS: f32 = 5.0;
+/// First-order all-pass stages in the phaser; each pair adds one notch
+const PHASER_STAGES: usize = 6;
+/// Range the phaser's all-pass corners sweep over, in Hz
+const PHASER_MIN_HZ: f32 = 200.0;
+const PHASER_MAX_HZ: f32 = 8000.0;
+/// Keeps the Hermite interpolator from reading samples that are not written yet
+const MIN_DELAY_SAMPLES: f32 = 3.0;
+/// Feedback is kept below 1 so the loop stays stable
+const MAX_FEEDBACK: f32 = 0.95;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ModulationSettings {
+    /// LFO rate in Hz
+    pub rate_hz: f32,
+    /// 0..1, how much of the sweep range the LFO covers
+    pub depth: f32,
+    /// -0.95..0.95, sign flips the comb/notch pattern
+    pub feedback: f32,
+    /// 0 (dry) .. 1 (wet)
+    pub mix: f32,
+    /// LFO phase difference between neighbouring channels, in degrees
+    pub stereo_phase_deg: f32,
+}
+
+impl ModulationSettings {
+    /// LFO for one channel, 0..1
+    fn lfo(&self, channel: usize, sample_rate: f32) -> impl FnMut() -> f32 {
+        let increment = 2.0 * PI * self.rate_hz.max(0.0) / sample_rate;
+        let mut phase = (channel as f32 * self.stereo_phase_deg).to_radians();
+        move || {
+            let value = 0.5 + 0.5 * phase.sin();
+            phase = (phase + increment) % (2.0 * PI);
+            value
+        }
+    }
+}
+
+/// Short modulated delays mixed with the dry signal
+pub fn apply_chorus(channels: &mut [Vec<f32>], sample_rate: u32, settings: ModulationSettings) {
+    modulated_delay(channels, sample_rate, settings, CHORUS_DELAY_MS - CHORUS_SWEEP_MS / 2.0, CHORUS_SWEEP_MS);
+}
+
+/// Very short modulated delay with feedback, sweeping a comb filter
+pub fn apply_flanger(channels: &mut [Vec<f32>], sample_rate: u32, settings: ModulationSettings) {
+    modulated_delay(channels, sample_rate, settings, FLANGER_DELAY_MS, FLANGER_SWEEP_MS);
+}
+
+/// Cascade of swept first-order all-passes mixed with the dry signal, giving moving notches
+pub fn apply_phaser(channels: &mut [Vec<f32>], sample_rate: u32, settings: ModulationSettings) {
+    let sample_rate = sample_rate as f32;
+    let feedback = settings.feedback.clamp(-MAX_FEEDBACK, MAX_FEEDBACK);
+    let mix = settings.mix.clamp(0.0, 1.0);
+    let depth = settings.depth.clamp(0.0, 1.0);
+    let max_hz = PHASER_MAX_HZ.min(sample_rate * 0.45);
+
+    for (c, channel) in channels.iter_mut().enumerate() {
+        let mut lfo = settings.lfo(c, sample_rate);
+        // (previous input, previous output) of each stage
+        let mut stages = [(0.0f32, 0.0f32); PHASER_STAGES];
+        let mut last = 0.0f32;
+
+        for sample in channel.iter_mut() {
+            let frequency = PHASER_MIN_HZ * (max_hz / PHASER_MIN_HZ).powf(depth * lfo());
+            let t = (PI * frequency / sample_rate).tan();
+            let a = (t - 1.0) / (t + 1.0);
+
+            let mut x = *sample + feedback * last;
+            for (x1, y1) in stages.iter_mut() {
+                let y = a * x + *x1 - a * *y1;
+                *x1 = x;
+                *y1 = y;
+                x = y;
+            }
+            last = x;
+
+            *sample = *sample * (1.0 - mix) + x * mix;
+        }
+    }
+}
+
+/// Delay line swept between `min_ms` and `min_ms + depth * sweep_ms`, read with
+/// 4-point Hermite interpolation
+fn modulated_delay(channels: &mut [Vec<f32>], sample_rate: u32, settings: ModulationSettings, min_ms: f32, sweep_ms: f32) {
+    let sample_rate = sample_rate as f32;
+    let feedback = settings.feedback.clamp(-MAX_FEEDBACK, MAX_FEEDBACK);
+    let mix = settings.mix.clamp(0.0, 1.0);
+    let min_delay = (min_ms * 0.001 * sample_rate).max(MIN_DELAY_SAMPLES);
+    let sweep = settings.depth.clamp(0.0, 1.0) * sweep_ms * 0.001 * sample_rate;
+    let size = (min_delay + sweep + 4.0).ceil() as usize;
+    let size = size.next_power_of_two();
+    let mask = size - 1;
+
+    for (c, channel) in channels.iter_mut().enumerate() {
+        let mut lfo = settings.lfo(c, sample_rate);
+        let mut line = vec![0.0f32; size];
+
+        for (n, sample) in channel.iter_mut().enumerate() {
+            // Read at n - delay: whole samples in usize, only the small delay in f32,
+            // so the fraction keeps its precision however long the song is
+            let delay = min_delay + sweep * lfo();
+            let whole = delay.ceil();
+            let index = n + size - whole as usize;
+            let fraction = whole - delay;
+            let tap = |offset: usize| line[(index + offset - 1) & mask];
+            let wet = hermite(tap(0), tap(1), tap(2), tap(3), fraction);
+
+            line[n & mask] = *sample + feedback * wet;
+            *sample = *sample * (1.0 - mix) + wet * mix;
+        }
+    }
+}
+
+/// Catmull-Rom interpolation between `x0` and `x1`
+pub fn hermite(xm1: f32, x0: f32, x1: f32, x2: f32, t: f32) -> f32 {
+    let c1 = 0.5 * (x1 - xm1);
+    let c2 = xm1 - 2.5 * x0 + 2.0 * x1 - 0.5 * x2;
+    let c3 = 0.5 * (x2 - xm1) + 1.5 * (x0 - x1);
+    ((c3 * t + c2) * t + c1) * t + x0
+}
@@ -0,0 +1,101 @@
+use crate::audio_types::{DetectionMode, GainReductionTrace, MultibandBand};
+use crate::biquad::{Biquad, BiquadCoefficients};
+use crate::compressor::{self, CompressorSettings};
+
+/// Butterworth Q; two cascaded sections make a 4th-order Linkwitz-Riley filter
+const BUTTERWORTH_Q: f64 = std::f64::consts::FRAC_1_SQRT_2;
+const KNEE_DB: f32 = 6.0;
+
+/// Settings used for bands the payload leaves out
+pub fn default_band() -> MultibandBand {
+    MultibandBand {
+        threshold: -18.0,
+        ratio: 2.0,
+        attack: 10.0,
+        release: 150.0,
+        makeup: 0.0,
+    }
+}
+
+/// Split every channel into bands at `crossovers`, compress each band on its
+/// own and sum them back. Returns the gain reduction trace of each band.
+pub fn apply_multiband(
+    channels: &mut [Vec<f32>],
+    sample_rate: u32,
+    crossovers: &[f32],
+    bands: &[MultibandBand],
+) -> Result<Vec<GainReductionTrace>, Box<dyn std::error::Error>> {
+    if !(2..=3).contains(&crossovers.len()) {
+        return Err(format!("Multiband compressor needs 2 or 3 crossovers, got {}", crossovers.len()).into());
+    }
+    if crossovers.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err("Multiband crossovers must be ascending".into());
+    }
+
+    let band_count = crossovers.len() + 1;
+    let mut split: Vec<Vec<Vec<f32>>> = vec![Vec::with_capacity(channels.len()); band_count];
+    for channel in channels.iter() {
+        for (band, signal) in split_bands(channel, sample_rate as f64, crossovers).into_iter().enumerate() {
+            split[band].push(signal);
+        }
+    }
+
+    let mut traces = Vec::with_capacity(band_count);
+    for (band, band_channels) in split.iter_mut().enumerate() {
+        let settings = bands.get(band).cloned().unwrap_or_else(default_band);
+        traces.push(compressor::apply_compressor(
+            band_channels,
+            sample_rate,
+            CompressorSettings {
+                threshold_db: settings.threshold,
+                ratio: settings.ratio,
+                knee_db: KNEE_DB,
+                attack_ms: settings.attack,
+                release_ms: settings.release,
+                makeup_db: settings.makeup,
+                auto_makeup: false,
+                detection: DetectionMode::Rms,
+                sidechain_hpf: None,
+            },
+        ));
+    }
+
+    for (c, channel) in channels.iter_mut().enumerate() {
+        channel.fill(0.0);
+        for band_channels in &split {
+            for (sample, band_sample) in channel.iter_mut().zip(&band_channels[c]) {
+                *sample += band_sample;
+            }
+        }
+    }
+
+    Ok(traces)
+}
+
+/// LR4 band split, lowest band first. Every band is passed through the
+/// all-pass of the crossovers it did not go through, so the bands stay in
+/// phase and sum to a flat magnitude response.
+fn split_bands(input: &[f32], sample_rate: f64, crossovers: &[f32]) -> Vec<Vec<f32>> {
+    let lr4 = |signal: &mut Vec<f32>, coefficients: BiquadCoefficients| {
+        for _ in 0..2 {
+            Biquad::new(coefficients).process_buffer(signal);
+        }
+    };
+
+    let mut bands = Vec::with_capacity(crossovers.len() + 1);
+    let mut rest = input.to_vec();
+    for (i, &frequency) in crossovers.iter().enumerate() {
+        let frequency = frequency as f64;
+        let mut low = rest.clone();
+        lr4(&mut low, BiquadCoefficients::low_pass(sample_rate, frequency, BUTTERWORTH_Q));
+        lr4(&mut rest, BiquadCoefficients::high_pass(sample_rate, frequency, BUTTERWORTH_Q));
+
+        for &higher in &crossovers[i + 1..] {
+            Biquad::new(BiquadCoefficients::all_pass(sample_rate, higher as f64, BUTTERWORTH_Q)).process_buffer(&mut low);
+        }
+        bands.push(low);
+    }
+    bands.push(rest);
+
+    bands
+}
@@ -0,0 +1,136 @@
+use crate::audio_types::{AudioBuffer, StemSeparationSettings, StemSet};
+
+/// Stems the model emits: drums, bass, other, vocals
+#[cfg(feature = "neural-separation")]
+const STEM_COUNT: usize = 4;
+#[cfg(feature = "neural-separation")]
+const DEFAULT_MODEL_RATE: u32 = 44100;
+#[cfg(feature = "neural-separation")]
+const DEFAULT_SEGMENT_SECONDS: f32 = 7.8;
+#[cfg(feature = "neural-separation")]
+const DEFAULT_OVERLAP: f32 = 0.25;
+
+/// Split a mix into vocals, drums, bass and other with a pretrained ONNX model on the CPU.
+/// `on_progress` receives the completed percentage after every chunk.
+#[cfg(feature = "neural-separation")]
+pub fn separate_stems(
+    audio_buffer: &AudioBuffer,
+    settings: &StemSeparationSettings,
+    mut on_progress: impl FnMut(f32),
+) -> Result<StemSet, Box<dyn std::error::Error>> {
+    use tract_onnx::prelude::*;
+
+    use crate::resampler;
+
+    if audio_buffer.channels.is_empty() {
+        return Err("Cannot separate an empty audio buffer".into());
+    }
+
+    let model_rate = settings.model_sample_rate.unwrap_or(DEFAULT_MODEL_RATE);
+    let segment = (settings.segment_seconds.unwrap_or(DEFAULT_SEGMENT_SECONDS) * model_rate as f32) as usize;
+    let overlap = settings.overlap.unwrap_or(DEFAULT_OVERLAP).clamp(0.0, 0.5);
+    let fade = (segment as f32 * overlap) as usize;
+    let hop = segment - fade;
+    if segment == 0 || hop == 0 {
+        return Err("Separation segment is too short".into());
+    }
+
+    let model = tract_onnx::onnx()
+        .model_for_path(&settings.model_path)?
+        .with_input_fact(0, InferenceFact::dt_shape(f32::datum_type(), tvec!(1, 2, segment)))?
+        // Exported models often declare symbolic output dims; infer them from the fixed chunk instead
+        .with_output_fact(0, InferenceFact::default())?
+        .into_optimized()?
+        .into_runnable()?;
+
+    // The model is stereo: duplicate mono, drop anything past the first two channels
+    let stereo = [
+        audio_buffer.channels[0].clone(),
+        audio_buffer.channels.get(1).unwrap_or(&audio_buffer.channels[0]).clone(),
+    ];
+    let input = resampler::resample_channels(&stereo, model_rate as f64 / audio_buffer.sample_rate as f64)?;
+    let len = input[0].len();
+
+    // Linear cross-fade over the overlap; `weight_sum` normalizes the overlap-add
+    let chunk_weight: Vec<f32> = (0..segment)
+        .map(|i| {
+            let edge = i.min(segment - 1 - i);
+            if edge < fade {
+                (edge + 1) as f32 / (fade + 1) as f32
+            } else {
+                1.0
+            }
+        })
+        .collect();
+    let mut weight_sum = vec![0.0f32; len];
+    let mut stems = vec![[vec![0.0f32; len], vec![0.0f32; len]]; STEM_COUNT];
+
+    let chunk_count = len.saturating_sub(fade).div_ceil(hop).max(1);
+    for chunk in 0..chunk_count {
+        let start = chunk * hop;
+        let valid = segment.min(len - start);
+
+        let waveform = tract_ndarray::Array3::<f32>::from_shape_fn((1, 2, segment), |(_, channel, i)| {
+            input[channel].get(start + i).copied().unwrap_or(0.0)
+        });
+        let outputs = model.run(tvec!(Tensor::from(waveform).into()))?;
+        let separated = outputs[0].to_array_view::<f32>()?;
+        if separated.shape() != [1, STEM_COUNT, 2, segment] {
+            return Err(format!("Unexpected model output shape {:?}", separated.shape()).into());
+        }
+
+        for i in 0..valid {
+            let weight = chunk_weight[i];
+            weight_sum[start + i] += weight;
+            for (stem, output) in stems.iter_mut().enumerate() {
+                for (channel, samples) in output.iter_mut().enumerate() {
+                    samples[start + i] += weight * separated[[0, stem, channel, i]];
+                }
+            }
+        }
+
+        on_progress((chunk + 1) as f32 / chunk_count as f32 * 100.0);
+    }
+
+    // Back to the source rate and channel layout
+    let to_buffer = |mut channels: [Vec<f32>; 2]| -> Result<AudioBuffer, Box<dyn std::error::Error>> {
+        for samples in channels.iter_mut() {
+            for (sample, &weight) in samples.iter_mut().zip(&weight_sum) {
+                *sample /= weight.max(1e-6);
+            }
+        }
+        let mut channels = resampler::resample_channels(&channels, audio_buffer.sample_rate as f64 / model_rate as f64)?;
+        if audio_buffer.channels.len() == 1 {
+            let right = channels.pop().unwrap_or_default();
+            for (left, right) in channels[0].iter_mut().zip(right) {
+                *left = 0.5 * (*left + right);
+            }
+        }
+        let frames = channels[0].len();
+
+        Ok(AudioBuffer {
+            channels,
+            sample_rate: audio_buffer.sample_rate,
+            duration: frames as f32 / audio_buffer.sample_rate as f32,
+        })
+    };
+
+    let mut stems = stems.into_iter();
+    let mut next_stem = || to_buffer(stems.next().ok_or("Missing model stem")?);
+    let drums = next_stem()?;
+    let bass = next_stem()?;
+    let other = next_stem()?;
+    let vocals = next_stem()?;
+
+    Ok(StemSet { vocals, drums, bass, other })
+}
+
+/// Neural separation needs the ONNX runtime, which is only linked with the `neural-separation` feature
+#[cfg(not(feature = "neural-separation"))]
+pub fn separate_stems(
+    _audio_buffer: &AudioBuffer,
+    _settings: &StemSeparationSettings,
+    _on_progress: impl FnMut(f32),
+) -> Result<StemSet, Box<dyn std::error::Error>> {
+    Err("Stem separation is not available: build with the `neural-separation` feature".into())
+}
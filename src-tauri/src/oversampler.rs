@@ -0,0 +1,83 @@
+use std::f64::consts::PI;
+
+/// Filter taps per polyphase branch; sets the steepness of the anti-imaging filter
+const TAPS_PER_PHASE: usize = 32;
+/// Passband edge as a fraction of the original Nyquist frequency
+const CUTOFF: f64 = 0.9;
+
+/// Zero-latency (non-causal) windowed-sinc oversampler for offline rendering.
+/// Upsampled sample `n * factor` lines up with input sample `n`.
+pub struct Oversampler {
+    factor: usize,
+    /// Symmetric low-pass at the upsampled rate, centered on `half_len`
+    kernel: Vec<f32>,
+    half_len: usize,
+}
+
+impl Oversampler {
+    pub fn new(factor: usize) -> Self {
+        let factor = factor.max(1);
+        let half_len = factor * TAPS_PER_PHASE / 2;
+        let bandwidth = CUTOFF / factor as f64;
+
+        let kernel = (0..=2 * half_len)
+            .map(|i| {
+                let t = i as f64 - half_len as f64;
+                let sinc = if t == 0.0 {
+                    1.0
+                } else {
+                    (PI * bandwidth * t).sin() / (PI * bandwidth * t)
+                };
+                // Blackman window over the whole kernel
+                let phase = 2.0 * PI * i as f64 / (2 * half_len) as f64;
+                let window = 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos();
+                (bandwidth * sinc * window) as f32
+            })
+            .collect();
+
+        Self { factor, kernel, half_len }
+    }
+
+    /// Interpolate to `factor` times the sample rate
+    pub fn upsample(&self, input: &[f32]) -> Vec<f32> {
+        if self.factor == 1 {
+            return input.to_vec();
+        }
+
+        let factor = self.factor as isize;
+        let half_len = self.half_len as isize;
+        let gain = self.factor as f32;
+
+        (0..input.len() * self.factor)
+            .map(|m| {
+                let m = m as isize;
+                // Input samples whose kernel reaches output position m
+                let first = (m - half_len + factor - 1).div_euclid(factor).max(0);
+                let last = ((m + half_len) / factor).min(input.len() as isize - 1);
+                (first..=last)
+                    .map(|k| input[k as usize] * self.kernel[(m - k * factor + half_len) as usize])
+                    .sum::<f32>()
+                    * gain
+            })
+            .collect()
+    }
+
+    /// Band-limit and decimate back to the original sample rate
+    pub fn downsample(&self, input: &[f32]) -> Vec<f32> {
+        if self.factor == 1 {
+            return input.to_vec();
+        }
+
+        let half_len = self.half_len as isize;
+        (0..input.len() / self.factor)
+            .map(|n| {
+                let center = (n * self.factor) as isize;
+                let first = (center - half_len).max(0);
+                let last = (center + half_len).min(input.len() as isize - 1);
+                (first..=last)
+                    .map(|j| input[j as usize] * self.kernel[(j - center + half_len) as usize])
+                    .sum::<f32>()
+            })
+            .collect()
+    }
+}
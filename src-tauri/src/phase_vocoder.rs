@@ -0,0 +1,438 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+use realfft::{ComplexToReal, RealFftPlanner, RealToComplex};
+use rustfft::num_complex::Complex32;
+
+use crate::resampler;
+
+/// Block size (samples) of the onset detector's energy envelope
+const ONSET_BLOCK: usize = 256;
+/// Sub-block size (samples) used to pin an onset down inside its detection block
+const ONSET_REFINE: usize = 16;
+/// Energy rise over the recent average that marks an onset (about 6 dB)
+const ONSET_RATIO: f32 = 4.0;
+/// Blocks quieter than this (mean square) never start an onset
+const ONSET_FLOOR: f32 = 1e-6;
+/// Cepstral lifter cutoff (seconds): keeps the envelope, drops the harmonics of voices up to ~650 Hz
+const LIFTER_QUEFRENCY: f32 = 0.0015;
+/// Largest boost the envelope warp may apply to a bin (about 24 dB)
+const MAX_ENVELOPE_GAIN: f32 = 16.0;
+
+/// Phase vocoder with identity phase locking. With transient handling on,
+/// frames around an onset are aligned to the onset's stretched time and read
+/// at the synthesis hop (no local stretch) with a phase reset, so attacks are
+/// reproduced on the grid instead of smeared.
+pub struct PhaseVocoder {
+    fft_size: usize,
+    hop: usize,
+    window: Vec<f32>,
+    window_norm: f32,
+    forward: Arc<dyn RealToComplex<f32>>,
+    inverse: Arc<dyn ComplexToReal<f32>>,
+    lifter_len: usize,
+    transient_handling: bool,
+}
+
+/// Per-frame analysis data driving the phase trajectory
+struct Frame {
+    magnitude: Vec<f32>,
+    phase: Vec<f64>,
+}
+
+impl PhaseVocoder {
+    pub fn new(sample_rate: u32, transient_handling: bool) -> Self {
+        // ~90 ms frames, 75% overlap
+        let fft_size = if sample_rate > 48000 { 8192 } else { 4096 };
+        let hop = fft_size / 4;
+        let window: Vec<f32> = apodize::hanning_iter(fft_size).map(|w| w as f32).collect();
+        let window_norm = window.iter().map(|w| w * w).sum::<f32>() / hop as f32;
+
+        let mut planner = RealFftPlanner::<f32>::new();
+        Self {
+            fft_size,
+            hop,
+            window,
+            window_norm,
+            forward: planner.plan_fft_forward(fft_size),
+            inverse: planner.plan_fft_inverse(fft_size),
+            lifter_len: ((sample_rate as f32 * LIFTER_QUEFRENCY) as usize).clamp(8, fft_size / 4),
+            transient_handling,
+        }
+    }
+
+    /// Change duration by `stretch` (output length / input length) without changing pitch.
+    /// All channels share one phase trajectory, so the stereo image stays coherent.
+    pub fn time_stretch(&self, channels: &[Vec<f32>], stretch: f64) -> Result<Vec<Vec<f32>>, Box<dyn std::error::Error>> {
+        self.process(channels, stretch, 1.0)
+    }
+
+    /// Shift pitch by `semitones` while keeping the duration: stretch in time, then resample back.
+    /// With `formant_semitones` set the spectral envelope is re-imposed so formants
+    /// move by that amount instead of following the pitch (0.0 preserves them).
+    pub fn pitch_shift(
+        &self,
+        channels: &[Vec<f32>],
+        semitones: f64,
+        formant_semitones: Option<f64>,
+    ) -> Result<Vec<Vec<f32>>, Box<dyn std::error::Error>> {
+        let ratio = 2.0_f64.powf(semitones / 12.0);
+        let input_len = channels.first().map_or(0, |c| c.len());
+
+        // Resampling scales the envelope by `ratio`, so pre-warp it by formant ratio / pitch ratio
+        let envelope_warp = match formant_semitones {
+            Some(formant_semitones) => 2.0_f64.powf(formant_semitones / 12.0) / ratio,
+            None => 1.0,
+        };
+
+        let stretched = self.process(channels, ratio, envelope_warp)?;
+        let mut shifted = resampler::resample_channels(&stretched, 1.0 / ratio)?;
+        for channel in &mut shifted {
+            channel.resize(input_len, 0.0);
+        }
+
+        Ok(shifted)
+    }
+
+    /// Time stretch by `stretch`, scaling the spectral envelope along the
+    /// frequency axis by `envelope_warp` (1.0 leaves it untouched)
+    fn process(
+        &self,
+        channels: &[Vec<f32>],
+        stretch: f64,
+        envelope_warp: f64,
+    ) -> Result<Vec<Vec<f32>>, Box<dyn std::error::Error>> {
+        if !(stretch.is_finite() && stretch > 0.0) {
+            return Err(format!("Invalid time stretch factor: {}", stretch).into());
+        }
+        if channels.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let n = self.fft_size;
+        let bins = n / 2 + 1;
+        let input_len = channels.iter().map(|c| c.len()).min().unwrap_or(0);
+        let output_len = (input_len as f64 * stretch).round() as usize;
+        let frame_count = output_len / self.hop + 2;
+
+        // Half a frame of padding centres the first frame on sample zero
+        let padded: Vec<Vec<f32>> = channels
+            .iter()
+            .map(|channel| {
+                let mut padded = vec![0.0f32; n / 2];
+                padded.extend_from_slice(&channel[..input_len]);
+                padded.resize(padded.len() + n, 0.0);
+                padded
+            })
+            .collect();
+        let max_start = padded[0].len() - n;
+
+        let onsets: Vec<usize> = if self.transient_handling {
+            let sum: Vec<f32> = (0..input_len).map(|i| channels.iter().map(|c| c[i]).sum()).collect();
+            detect_onsets(&sum).into_iter().map(|onset| onset + n / 2).collect()
+        } else {
+            Vec::new()
+        };
+        let positions = self.analysis_positions(frame_count, stretch, &onsets, max_start);
+
+        let mut outputs = vec![vec![0.0f32; frame_count * self.hop + n]; channels.len()];
+        let mut time_buffer = vec![0.0f32; n];
+        let mut spectra = vec![vec![Complex32::new(0.0, 0.0); bins]; channels.len()];
+        let mut synthesis_phase = vec![0.0f64; bins];
+        let mut transform = vec![Complex32::new(1.0, 0.0); bins];
+        let mut envelope_gain = vec![1.0f32; bins];
+        let mut cepstrum_buffer = vec![Complex32::new(0.0, 0.0); bins];
+        let mut previous: Option<(usize, Frame)> = None;
+
+        for (t, &(start, reset)) in positions.iter().enumerate() {
+            for (channel, spectrum) in padded.iter().zip(spectra.iter_mut()) {
+                self.analyze(&channel[start..start + n], &mut time_buffer, spectrum)?;
+            }
+            let frame = reference_frame(&spectra);
+
+            match &previous {
+                Some((previous_start, previous_frame)) if !reset => {
+                    let advance = self.phase_advance(previous_frame, &frame, start - previous_start);
+                    lock_phases(&frame, &advance, &mut synthesis_phase);
+                }
+                // First frame or onset: take the analysis phases as they are
+                _ => synthesis_phase.copy_from_slice(&frame.phase),
+            }
+
+            if (envelope_warp - 1.0).abs() > 1e-6 {
+                self.envelope_gain(&frame.magnitude, envelope_warp, &mut cepstrum_buffer, &mut time_buffer, &mut envelope_gain)?;
+            }
+
+            // Every channel gets the same per-bin phase rotation (and envelope gain) as the reference
+            for (((factor, &synthesis), &analysis), &gain) in transform
+                .iter_mut()
+                .zip(&synthesis_phase)
+                .zip(&frame.phase)
+                .zip(&envelope_gain)
+            {
+                *factor = Complex32::from_polar(gain, (synthesis - analysis) as f32);
+            }
+
+            let offset = t * self.hop;
+            for (spectrum, output) in spectra.iter_mut().zip(outputs.iter_mut()) {
+                self.synthesize(spectrum, &transform, &mut time_buffer)?;
+                for (out, &sample) in output[offset..offset + n].iter_mut().zip(&time_buffer) {
+                    *out += sample;
+                }
+            }
+
+            previous = Some((start, frame));
+        }
+
+        for output in &mut outputs {
+            output.drain(..n / 2);
+            output.truncate(output_len);
+        }
+        Ok(outputs)
+    }
+
+    /// Analysis frame start for every synthesis frame, plus whether the frame
+    /// starts a transient (phase reset). Frames holding an onset in their last
+    /// three quarters are snapped so the onset lands exactly at its stretched
+    /// time and then advance by the synthesis hop; the drift this causes is
+    /// worked off by the following frames.
+    fn analysis_positions(
+        &self,
+        frame_count: usize,
+        stretch: f64,
+        onsets: &[usize],
+        max_start: usize,
+    ) -> Vec<(usize, bool)> {
+        let n = self.fft_size as f64;
+        let synthesis_hop = self.hop as f64;
+        let analysis_hop = synthesis_hop / stretch;
+        let max_hop = (2.0 * analysis_hop).min(n / 2.0).max(analysis_hop);
+
+        let mut positions = Vec::with_capacity(frame_count);
+        let mut position = 0.0f64;
+        let mut next_onset = 0;
+        let mut previous_onset = None;
+
+        for t in 0..frame_count {
+            let scheduled = t as f64 * analysis_hop;
+            let synthesis_start = t as f64 * synthesis_hop;
+
+            // Where an onset sits inside frame t once the frame is snapped to it.
+            // Onsets are in padded input samples; the output drops the same n / 2 padding.
+            let offset_in_frame = |onset: usize| n / 2.0 + (onset as f64 - n / 2.0) * stretch - synthesis_start;
+
+            while next_onset < onsets.len() && offset_in_frame(onsets[next_onset]) < n / 4.0 {
+                next_onset += 1;
+            }
+            let snapped = onsets
+                .get(next_onset)
+                .filter(|&&onset| offset_in_frame(onset) < n)
+                .map(|&onset| onset as f64 - offset_in_frame(onset))
+                .filter(|&snapped| snapped >= 0.0 && (snapped - position).abs() < n);
+
+            let locked_onset = snapped.map(|snapped| {
+                position = snapped;
+                next_onset
+            });
+            let reset = locked_onset.is_some() && locked_onset != previous_onset;
+            positions.push(((position.round() as usize).min(max_start), reset));
+            previous_onset = locked_onset;
+
+            position += if locked_onset.is_some() {
+                synthesis_hop
+            } else {
+                let catch_up = scheduled + analysis_hop - position;
+                (0.75 * analysis_hop + 0.25 * catch_up).clamp(0.25 * analysis_hop, max_hop)
+            };
+        }
+
+        positions
+    }
+
+    fn analyze(
+        &self,
+        samples: &[f32],
+        time_buffer: &mut [f32],
+        spectrum: &mut [Complex32],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for ((dst, &src), &w) in time_buffer.iter_mut().zip(samples).zip(&self.window) {
+            *dst = src * w;
+        }
+        self.forward.process(time_buffer, spectrum)?;
+        Ok(())
+    }
+
+    fn synthesize(
+        &self,
+        spectrum: &mut [Complex32],
+        transform: &[Complex32],
+        time_buffer: &mut [f32],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for (bin, factor) in spectrum.iter_mut().zip(transform) {
+            *bin *= factor;
+        }
+        // The real inverse transform requires purely real DC and Nyquist bins
+        let last = spectrum.len() - 1;
+        spectrum[0].im = 0.0;
+        spectrum[last].im = 0.0;
+
+        self.inverse.process(spectrum, time_buffer)?;
+
+        let scale = 1.0 / (self.fft_size as f32 * self.window_norm);
+        for (sample, &w) in time_buffer.iter_mut().zip(&self.window) {
+            *sample *= w * scale;
+        }
+        Ok(())
+    }
+
+    /// Per-bin gain that moves the cepstral envelope E(k) to E(k / warp)
+    fn envelope_gain(
+        &self,
+        magnitude: &[f32],
+        warp: f64,
+        cepstrum_buffer: &mut [Complex32],
+        time_buffer: &mut [f32],
+        gain: &mut [f32],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let n = self.fft_size;
+
+        // Real cepstrum of the log magnitude
+        for (bin, &m) in cepstrum_buffer.iter_mut().zip(magnitude) {
+            *bin = Complex32::new((m + 1e-9).ln(), 0.0);
+        }
+        self.inverse.process(cepstrum_buffer, time_buffer)?;
+
+        // Low-quefrency lifter keeps the smooth envelope
+        let scale = 1.0 / n as f32;
+        for (q, sample) in time_buffer.iter_mut().enumerate() {
+            let keep = q < self.lifter_len || q > n - self.lifter_len;
+            *sample = if keep { *sample * scale } else { 0.0 };
+        }
+        self.forward.process(time_buffer, cepstrum_buffer)?;
+        let log_envelope: Vec<f32> = cepstrum_buffer.iter().map(|c| c.re).collect();
+
+        let last = log_envelope.len() - 1;
+        for (k, g) in gain.iter_mut().enumerate() {
+            let source = k as f64 / warp;
+            let warped = if source >= last as f64 {
+                log_envelope[last]
+            } else {
+                let i = source as usize;
+                let frac = (source - i as f64) as f32;
+                log_envelope[i] * (1.0 - frac) + log_envelope[i + 1] * frac
+            };
+            *g = (warped - log_envelope[k]).exp().min(MAX_ENVELOPE_GAIN);
+        }
+
+        Ok(())
+    }
+
+    /// Synthesis phase increment per bin from the instantaneous frequency
+    fn phase_advance(&self, previous: &Frame, current: &Frame, analysis_hop: usize) -> Vec<f64> {
+        let n = self.fft_size as f64;
+        let synthesis_hop = self.hop as f64;
+
+        (0..current.phase.len())
+            .map(|k| {
+                let omega = 2.0 * PI * k as f64 / n;
+                if analysis_hop == 0 {
+                    return omega * synthesis_hop;
+                }
+                let deviation = wrap_phase(current.phase[k] - previous.phase[k] - omega * analysis_hop as f64);
+                (omega + deviation / analysis_hop as f64) * synthesis_hop
+            })
+            .collect()
+    }
+}
+
+/// Reference for phase tracking: summed magnitudes for peak picking and the
+/// phase of the channel sum (the mid signal for stereo)
+fn reference_frame(spectra: &[Vec<Complex32>]) -> Frame {
+    let bins = spectra[0].len();
+    let mut magnitude = vec![0.0f32; bins];
+    let mut sum = vec![Complex32::new(0.0, 0.0); bins];
+
+    for spectrum in spectra {
+        for ((m, s), bin) in magnitude.iter_mut().zip(sum.iter_mut()).zip(spectrum) {
+            *m += bin.norm();
+            *s += bin;
+        }
+    }
+
+    Frame {
+        magnitude,
+        phase: sum.iter().map(|c| c.arg() as f64).collect(),
+    }
+}
+
+/// Identity phase locking: peaks advance by their instantaneous frequency,
+/// the bins around each peak keep their phase relation to it
+fn lock_phases(frame: &Frame, advance: &[f64], synthesis_phase: &mut [f64]) {
+    let magnitude = &frame.magnitude;
+    let bins = magnitude.len();
+
+    let peaks: Vec<usize> = (0..bins)
+        .filter(|&k| {
+            let lo = k.saturating_sub(2);
+            let hi = (k + 2).min(bins - 1);
+            magnitude[k] > 0.0 && (lo..=hi).all(|j| j == k || magnitude[k] >= magnitude[j])
+        })
+        .collect();
+
+    if peaks.is_empty() {
+        for (phase, &delta) in synthesis_phase.iter_mut().zip(advance) {
+            *phase = wrap_phase(*phase + delta);
+        }
+        return;
+    }
+
+    let peak_phase: Vec<f64> = peaks
+        .iter()
+        .map(|&p| wrap_phase(synthesis_phase[p] + advance[p]))
+        .collect();
+
+    // Each bin follows the nearest peak (region boundaries halfway between peaks)
+    let mut region = 0;
+    for (k, phase) in synthesis_phase.iter_mut().enumerate() {
+        while region + 1 < peaks.len() && k > (peaks[region] + peaks[region + 1]) / 2 {
+            region += 1;
+        }
+        let p = peaks[region];
+        *phase = wrap_phase(peak_phase[region] + frame.phase[k] - frame.phase[p]);
+    }
+}
+
+/// Sample positions where the short-term energy jumps well above its recent average
+fn detect_onsets(input: &[f32]) -> Vec<usize> {
+    let energies: Vec<f32> = input
+        .chunks(ONSET_BLOCK)
+        .map(|block| block.iter().map(|s| s * s).sum::<f32>() / block.len() as f32)
+        .collect();
+
+    let mut onsets = Vec::new();
+    let mut last_onset_block = None;
+
+    for (b, &energy) in energies.iter().enumerate().skip(1) {
+        let history = &energies[b.saturating_sub(8)..b];
+        let average = history.iter().sum::<f32>() / history.len() as f32;
+        let recent = last_onset_block.is_some_and(|last| b - last <= 4);
+
+        if energy > ONSET_FLOOR && energy > ONSET_RATIO * average && !recent {
+            // First short sub-block over the threshold, so the onset is placed to within a few samples
+            let search_start = (b - 1) * ONSET_BLOCK;
+            let search_end = ((b + 1) * ONSET_BLOCK).min(input.len());
+            let refined = input[search_start..search_end]
+                .chunks(ONSET_REFINE)
+                .position(|sub| sub.iter().map(|s| s * s).sum::<f32>() / sub.len() as f32 > ONSET_RATIO * average)
+                .map_or(b * ONSET_BLOCK, |i| search_start + i * ONSET_REFINE);
+
+            onsets.push(refined);
+            last_onset_block = Some(b);
+        }
+    }
+
+    onsets
+}
+
+fn wrap_phase(phase: f64) -> f64 {
+    phase - 2.0 * PI * ((phase + PI) / (2.0 * PI)).floor()
+}
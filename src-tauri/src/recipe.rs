@@ -0,0 +1,213 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sha2::{Digest, Sha256};
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, Tag};
+use symphonia::core::probe::Hint;
+
+use crate::audio_types::{AdvancedAudioEffects, ProcessingRecipe};
+use crate::wav_chunks::{self, RiffChunk};
+
+/// RIFF chunk id holding the JSON recipe in exported WAV files
+pub const RECIPE_CHUNK_ID: &[u8; 4] = b"ezrc";
+
+/// Tag name used for the JSON recipe in formats that carry text tags
+pub const RECIPE_TAG: &str = "EZ_AUDIO_RECIPE";
+
+/// ID3v2 text encoding byte for UTF-8
+const ID3_UTF8: u8 = 3;
+
+/// Capture the settings and provenance of a render
+pub fn build_recipe(
+    effects: &AdvancedAudioEffects,
+    source_path: Option<&str>,
+) -> Result<ProcessingRecipe, Box<dyn std::error::Error>> {
+    let source_hash = match source_path {
+        Some(path) => Some(hash_file(path)?),
+        None => None,
+    };
+
+    Ok(ProcessingRecipe {
+        effects: effects.clone(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        source_hash,
+        processed_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+    })
+}
+
+/// SHA-256 of a file's contents as lowercase hex
+pub fn hash_file(file_path: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let mut file = File::open(file_path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Serialize a recipe into the WAV chunk written after the sample data
+pub fn encode_chunk(recipe: &ProcessingRecipe) -> Result<RiffChunk, Box<dyn std::error::Error>> {
+    Ok(RiffChunk::new(RECIPE_CHUNK_ID, serde_json::to_vec(recipe)?))
+}
+
+/// Serialize a recipe into an ID3v2.4 tag with one TXXX frame, to be placed
+/// in front of the MP3 frames. ID3v1 and LAME's tag fields are far too short
+/// for the JSON, so the tag is written here instead.
+pub fn encode_id3_tag(recipe: &ProcessingRecipe) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut frame_body = vec![ID3_UTF8];
+    frame_body.extend_from_slice(RECIPE_TAG.as_bytes());
+    frame_body.push(0);
+    frame_body.extend_from_slice(&serde_json::to_vec(recipe)?);
+
+    let mut frame = b"TXXX".to_vec();
+    frame.extend_from_slice(&syncsafe(frame_body.len())?);
+    frame.extend_from_slice(&[0, 0]);
+    frame.extend_from_slice(&frame_body);
+
+    let mut tag = b"ID3".to_vec();
+    tag.extend_from_slice(&[4, 0, 0]);
+    tag.extend_from_slice(&syncsafe(frame.len())?);
+    tag.extend_from_slice(&frame);
+
+    Ok(tag)
+}
+
+/// ID3v2 size field: 28 bits spread over four bytes with the top bit clear
+fn syncsafe(size: usize) -> Result<[u8; 4], Box<dyn std::error::Error>> {
+    if size >= 1 << 28 {
+        return Err("Recipe is too large for an ID3v2 tag".into());
+    }
+    Ok([(size >> 21) as u8 & 0x7f, (size >> 14) as u8 & 0x7f, (size >> 7) as u8 & 0x7f, size as u8 & 0x7f])
+}
+
+/// Read the embedded recipe back from an exported file, if it has one
+pub fn read_recipe(file_path: &str) -> Result<Option<ProcessingRecipe>, Box<dyn std::error::Error>> {
+    let extension = Path::new(file_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    if extension == "wav" {
+        let chunks = wav_chunks::read_chunks(file_path)?;
+        return Ok(chunks
+            .iter()
+            .find(|chunk| &chunk.id == RECIPE_CHUNK_ID)
+            .and_then(|chunk| serde_json::from_slice(&chunk.data).ok()));
+    }
+
+    // Other formats: look through the text tags for a recipe payload
+    let file = File::open(file_path)?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension(&extension);
+
+    let mut probed = symphonia::default::get_probe().format(
+        &hint,
+        mss,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+
+    if let Some(recipe) = probed
+        .metadata
+        .get()
+        .and_then(|metadata| metadata.current().and_then(|rev| find_recipe_tag(rev.tags())))
+    {
+        return Ok(Some(recipe));
+    }
+
+    Ok(probed
+        .format
+        .metadata()
+        .current()
+        .and_then(|rev| find_recipe_tag(rev.tags())))
+}
+
+fn find_recipe_tag(tags: &[Tag]) -> Option<ProcessingRecipe> {
+    let value_of = |tag: &Tag| {
+        let value = tag.value.to_string();
+        let payload = value
+            .strip_prefix(RECIPE_TAG)
+            .and_then(|rest| rest.strip_prefix('='))
+            .unwrap_or(&value);
+        serde_json::from_str::<ProcessingRecipe>(payload).ok()
+    };
+
+    tags.iter()
+        .filter(|tag| tag.key.to_uppercase().contains(RECIPE_TAG))
+        .find_map(value_of)
+        .or_else(|| tags.iter().find_map(value_of))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_recipe() -> ProcessingRecipe {
+        let effects: AdvancedAudioEffects = serde_json::from_value(serde_json::json!({
+            "reverb": 0.0,
+            "bass_boost": 0.0,
+            "tempo": 0.8,
+            "volume": 1.0,
+            "eq_low": 0.7,
+        }))
+        .unwrap();
+        build_recipe(&effects, None).unwrap()
+    }
+
+    fn assert_same(read: Option<ProcessingRecipe>, written: &ProcessingRecipe) {
+        let read = read.expect("recipe not found in file");
+        assert_eq!(serde_json::to_value(&read).unwrap(), serde_json::to_value(written).unwrap());
+    }
+
+    #[test]
+    fn id3_recipe_round_trip() {
+        let recipe = test_recipe();
+        let mut data = encode_id3_tag(&recipe).unwrap();
+        // Silent MPEG-1 Layer III frames (128 kbps, 44.1 kHz) so the file probes as MP3
+        for _ in 0..8 {
+            let mut frame = vec![0u8; 417];
+            frame[..4].copy_from_slice(&[0xff, 0xfb, 0x90, 0x00]);
+            data.extend_from_slice(&frame);
+        }
+
+        let path = std::env::temp_dir().join("ez-audio-recipe-id3.mp3");
+        std::fs::write(&path, data).unwrap();
+        let read = read_recipe(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_same(read, &recipe);
+    }
+
+    #[cfg(feature = "mp3")]
+    #[test]
+    fn mp3_export_recipe_round_trip() {
+        use crate::audio_types::AudioBuffer;
+
+        let recipe = test_recipe();
+        let audio = AudioBuffer {
+            channels: vec![vec![0.0; 44100]; 2],
+            sample_rate: 44100,
+            duration: 1.0,
+        };
+
+        let path = std::env::temp_dir().join("ez-audio-recipe-export.mp3");
+        let path = path.to_str().unwrap();
+        crate::audio_loader::AudioLoader::save_as_mp3(&audio, path, 192, Some(&recipe)).unwrap();
+        let read = read_recipe(path).unwrap();
+        std::fs::remove_file(path).ok();
+
+        assert_same(read, &recipe);
+    }
+}
@@ -0,0 +1,83 @@
+use rubato::{
+    Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction,
+};
+
+use crate::audio_types::AudioBuffer;
+
+const CHUNK_SIZE: usize = 4096;
+
+/// Convert an audio buffer to a new sample rate
+pub fn resample_buffer(
+    audio_buffer: &AudioBuffer,
+    target_rate: u32,
+) -> Result<AudioBuffer, Box<dyn std::error::Error>> {
+    if target_rate == audio_buffer.sample_rate {
+        return Ok(audio_buffer.clone());
+    }
+
+    let ratio = target_rate as f64 / audio_buffer.sample_rate as f64;
+    let channels = resample_channels(&audio_buffer.channels, ratio)?;
+    let frames = channels.first().map_or(0, |c| c.len());
+
+    Ok(AudioBuffer {
+        channels,
+        sample_rate: target_rate,
+        duration: frames as f32 / target_rate as f32,
+    })
+}
+
+/// Resample every channel by `ratio` (output length / input length) with
+/// band-limited sinc interpolation
+pub fn resample_channels(
+    channels: &[Vec<f32>],
+    ratio: f64,
+) -> Result<Vec<Vec<f32>>, Box<dyn std::error::Error>> {
+    if channels.is_empty() || (ratio - 1.0).abs() < 1e-9 {
+        return Ok(channels.to_vec());
+    }
+    if !(ratio.is_finite() && ratio > 0.0) {
+        return Err(format!("Invalid resample ratio: {}", ratio).into());
+    }
+
+    let parameters = SincInterpolationParameters {
+        sinc_len: 256,
+        f_cutoff: 0.95,
+        interpolation: SincInterpolationType::Cubic,
+        oversampling_factor: 256,
+        window: WindowFunction::BlackmanHarris2,
+    };
+    let mut resampler = SincFixedIn::<f32>::new(ratio, 1.0, parameters, CHUNK_SIZE, channels.len())?;
+
+    let input_len = channels.iter().map(|c| c.len()).min().unwrap_or(0);
+    let expected_len = (input_len as f64 * ratio).round() as usize;
+    let mut output = vec![Vec::with_capacity(expected_len); channels.len()];
+
+    let mut position = 0;
+    while position + CHUNK_SIZE <= input_len {
+        let chunk: Vec<&[f32]> = channels.iter().map(|c| &c[position..position + CHUNK_SIZE]).collect();
+        append_frames(&mut output, resampler.process(&chunk, None)?);
+        position += CHUNK_SIZE;
+    }
+
+    if position < input_len {
+        let chunk: Vec<&[f32]> = channels.iter().map(|c| &c[position..input_len]).collect();
+        append_frames(&mut output, resampler.process_partial(Some(&chunk), None)?);
+    }
+
+    // Flush the samples still held back by the interpolation filter
+    while output[0].len() < expected_len {
+        append_frames(&mut output, resampler.process_partial::<&[f32]>(None, None)?);
+    }
+
+    for channel in &mut output {
+        channel.truncate(expected_len);
+    }
+
+    Ok(output)
+}
+
+fn append_frames(output: &mut [Vec<f32>], frames: Vec<Vec<f32>>) {
+    for (channel, new_frames) in output.iter_mut().zip(frames) {
+        channel.extend_from_slice(&new_frames);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::audio_types::SaturationCurve;
+use crate::biquad::{Biquad, BiquadCoefficients};
+use crate::oversampler::Oversampler;
+
+/// Input offset of the tube curve; the asymmetry adds even harmonics
+const TUBE_BIAS: f32 = 0.2;
+/// Corner of the filter removing the DC the tube curve's asymmetry leaves behind, in Hz
+const DC_BLOCK_HZ: f64 = 10.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SaturationSettings {
+    pub curve: SaturationCurve,
+    /// Gain into the curve in dB
+    pub drive_db: f32,
+    /// Gain after the curve in dB
+    pub output_db: f32,
+    /// 0 (dry) .. 1 (wet)
+    pub mix: f32,
+    /// 1, 2, 4 or 8
+    pub oversampling: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BitcrusherSettings {
+    /// Quantizer resolution, 1..24 bits
+    pub bits: u32,
+    /// Rate the signal is sampled and held at, in Hz
+    pub sample_rate_hz: Option<f32>,
+    /// 0 (dry) .. 1 (wet)
+    pub mix: f32,
+    /// 1, 2, 4 or 8; used by the quantizer when the sample rate is kept
+    pub oversampling: usize,
+}
+
+/// Transfer curve for one sample; every curve maps 0 to 0 and has unity slope near it
+pub fn shape(curve: SaturationCurve, x: f32) -> f32 {
+    match curve {
+        SaturationCurve::Tape => x.tanh(),
+        SaturationCurve::Tube => (x + TUBE_BIAS).tanh() - TUBE_BIAS.tanh(),
+        SaturationCurve::SoftClip => {
+            // Cubic, reaching the ceiling with zero slope at |x| = 1.5
+            let x = x.clamp(-1.5, 1.5);
+            x - 4.0 / 27.0 * x * x * x
+        }
+        SaturationCurve::HardClip => x.clamp(-1.0, 1.0),
+        SaturationCurve::Foldback => {
+            // Reflect everything beyond ±1 back into range (a triangle wave in x)
+            let t = (x + 1.0).rem_euclid(4.0);
+            if t < 2.0 { t - 1.0 } else { 3.0 - t }
+        }
+    }
+}
+
+/// Drive every channel through `settings.curve` at the oversampled rate
+pub fn apply_saturation(
+    channels: &mut [Vec<f32>],
+    sample_rate: u32,
+    settings: SaturationSettings,
+) -> Result<(), Box<dyn std::error::Error>> {
+    check_oversampling(settings.oversampling)?;
+
+    let oversampler = Oversampler::new(settings.oversampling);
+    let drive = 10.0_f32.powf(settings.drive_db / 20.0);
+    let output = 10.0_f32.powf(settings.output_db / 20.0);
+    let mix = settings.mix.clamp(0.0, 1.0);
+
+    for channel in channels.iter_mut() {
+        let mut upsampled = oversampler.upsample(channel);
+        for sample in upsampled.iter_mut() {
+            *sample = shape(settings.curve, *sample * drive);
+        }
+        let mut wet = oversampler.downsample(&upsampled);
+
+        if settings.curve == SaturationCurve::Tube {
+            Biquad::new(BiquadCoefficients::high_pass(sample_rate as f64, DC_BLOCK_HZ, 0.707)).process_buffer(&mut wet);
+        }
+
+        for (sample, wet) in channel.iter_mut().zip(wet) {
+            *sample = *sample * (1.0 - mix) + wet * output * mix;
+        }
+    }
+
+    Ok(())
+}
+
+/// Bit depth and sample-rate reduction. With only the bit depth reduced the
+/// quantizer runs oversampled like the saturation curves. With a reduced rate
+/// everything runs at the original rate on purpose: the aliasing of the
+/// sample-and-hold is the sound being asked for.
+pub fn apply_bitcrusher(
+    channels: &mut [Vec<f32>],
+    sample_rate: u32,
+    settings: BitcrusherSettings,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let steps = 2.0_f32.powi(settings.bits.clamp(1, 24) as i32 - 1);
+    let mix = settings.mix.clamp(0.0, 1.0);
+    let quantize = |sample: f32| (sample * steps).round() / steps;
+
+    let Some(reduced_rate) = settings.sample_rate_hz else {
+        check_oversampling(settings.oversampling)?;
+        let oversampler = Oversampler::new(settings.oversampling);
+        for channel in channels.iter_mut() {
+            let upsampled: Vec<f32> = oversampler.upsample(channel).into_iter().map(quantize).collect();
+            let wet = oversampler.downsample(&upsampled);
+            for (sample, wet) in channel.iter_mut().zip(wet) {
+                *sample = *sample * (1.0 - mix) + wet * mix;
+            }
+        }
+        return Ok(());
+    };
+
+    let hold_step = (reduced_rate / sample_rate as f32).clamp(0.0, 1.0);
+    for channel in channels.iter_mut() {
+        // Phase accumulator: a new sample is taken each time it wraps
+        let mut phase = 1.0f32;
+        let mut held = 0.0f32;
+        for sample in channel.iter_mut() {
+            phase += hold_step;
+            if phase >= 1.0 {
+                phase -= 1.0;
+                held = quantize(*sample);
+            }
+            *sample = *sample * (1.0 - mix) + held * mix;
+        }
+    }
+
+    Ok(())
+}
+
+fn check_oversampling(factor: usize) -> Result<(), Box<dyn std::error::Error>> {
+    if [1, 2, 4, 8].contains(&factor) {
+        Ok(())
+    } else {
+        Err(format!("Oversampling must be 1, 2, 4 or 8, got {}", factor).into())
+    }
+}
@@ -0,0 +1,90 @@
+use std::f32::consts::{FRAC_PI_4, SQRT_2};
+use crate::audio_types::{MidSideChannel, PanLaw};
+
+#[derive(Debug, Clone, Copy)]
+pub struct StereoSettings {
+    /// Side level factor: 0 folds to mono, 1 leaves the image unchanged, 2 doubles the side signal
+    pub width: f32,
+    pub mid_gain_db: f32,
+    pub side_gain_db: f32,
+    /// -1 (left) .. 1 (right)
+    pub balance: f32,
+    pub pan_law: PanLaw,
+}
+
+/// Convert left/right into mid/side in place: M = (L + R) / 2, S = (L - R) / 2
+pub fn encode_mid_side(left: &mut [f32], right: &mut [f32]) {
+    for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+        let mid = (*l + *r) * 0.5;
+        let side = (*l - *r) * 0.5;
+        *l = mid;
+        *r = side;
+    }
+}
+
+/// Inverse of `encode_mid_side`: L = M + S, R = M - S
+pub fn decode_mid_side(mid: &mut [f32], side: &mut [f32]) {
+    for (m, s) in mid.iter_mut().zip(side.iter_mut()) {
+        let left = *m + *s;
+        let right = *m - *s;
+        *m = left;
+        *s = right;
+    }
+}
+
+/// Run `process` on the whole buffer, or only on its mid or side signal.
+/// Buffers that are not stereo are always processed as a whole.
+pub fn with_mid_side<R>(
+    channels: &mut [Vec<f32>],
+    target: Option<MidSideChannel>,
+    process: impl FnOnce(&mut [Vec<f32>]) -> R,
+) -> R {
+    let Some(target) = target.filter(|_| channels.len() == 2) else {
+        return process(channels);
+    };
+
+    let (left, right) = channels.split_at_mut(1);
+    encode_mid_side(&mut left[0], &mut right[0]);
+
+    let index = match target {
+        MidSideChannel::Mid => 0,
+        MidSideChannel::Side => 1,
+    };
+    let result = process(&mut channels[index..=index]);
+
+    let (mid, side) = channels.split_at_mut(1);
+    decode_mid_side(&mut mid[0], &mut side[0]);
+
+    result
+}
+
+/// Width, mid/side gain and balance for a stereo buffer; other layouts are left alone
+pub fn apply_stereo(channels: &mut [Vec<f32>], settings: StereoSettings) {
+    let [left, right] = channels else {
+        return;
+    };
+
+    let mid_gain = 10.0_f32.powf(settings.mid_gain_db / 20.0);
+    let side_gain = 10.0_f32.powf(settings.side_gain_db / 20.0) * settings.width.clamp(0.0, 2.0);
+    let (left_gain, right_gain) = pan_gains(settings.balance, settings.pan_law);
+
+    for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+        let mid = (*l + *r) * 0.5 * mid_gain;
+        let side = (*l - *r) * 0.5 * side_gain;
+        *l = (mid + side) * left_gain;
+        *r = (mid - side) * right_gain;
+    }
+}
+
+/// Left and right gains for a balance position, unity for both at the center
+fn pan_gains(balance: f32, pan_law: PanLaw) -> (f32, f32) {
+    let position = balance.clamp(-1.0, 1.0);
+    match pan_law {
+        PanLaw::Balance => ((1.0 - position).min(1.0), (1.0 + position).min(1.0)),
+        PanLaw::ConstantPower => {
+            let angle = (position + 1.0) * FRAC_PI_4;
+            (angle.cos() * SQRT_2, angle.sin() * SQRT_2)
+        }
+        PanLaw::Linear => (1.0 - position, 1.0 + position),
+    }
+}
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+use realfft::{ComplexToReal, RealFftPlanner, RealToComplex};
+use rustfft::num_complex::Complex32;
+
+/// Vocal band edges (Hz): kick and bass below, cymbals and air above, stay in the instrumental
+const VOCAL_LOW_HZ: f32 = 120.0;
+const VOCAL_HIGH_HZ: f32 = 12000.0;
+/// Per-frame smoothing of the mask, trades musical noise against vocal onsets
+const MASK_SMOOTHING: f32 = 0.4;
+
+/// Center-channel extractor: a soft time-frequency mask keeps the bins where
+/// left and right agree in level and phase, which is where a lead vocal is usually mixed.
+pub struct VocalSeparator {
+    fft_size: usize,
+    hop: usize,
+    window: Vec<f32>,
+    window_norm: f32,
+    forward: Arc<dyn RealToComplex<f32>>,
+    inverse: Arc<dyn ComplexToReal<f32>>,
+    band_weight: Vec<f32>,
+    mask_exponent: f32,
+}
+
+/// Center estimate and the remaining stereo instrumental
+pub struct SeparatedStems {
+    pub vocal: Vec<f32>,
+    pub instrumental: [Vec<f32>; 2],
+}
+
+impl VocalSeparator {
+    /// `sensitivity` 0..1: higher values pull in bins that are only roughly centered
+    pub fn new(sample_rate: u32, sensitivity: f32) -> Self {
+        let fft_size = if sample_rate > 48000 { 8192 } else { 4096 };
+        let hop = fft_size / 4;
+        let window: Vec<f32> = apodize::hanning_iter(fft_size).map(|w| w as f32).collect();
+        let window_norm = window.iter().map(|w| w * w).sum::<f32>() / hop as f32;
+
+        // Soft band-pass over the vocal range, one weight per bin
+        let bin_hz = sample_rate as f32 / fft_size as f32;
+        let band_weight = (0..fft_size / 2 + 1)
+            .map(|k| {
+                let f = (k as f32 * bin_hz).max(1.0);
+                let high_pass = 1.0 / (1.0 + (VOCAL_LOW_HZ / f).powi(4));
+                let low_pass = 1.0 / (1.0 + (f / VOCAL_HIGH_HZ).powi(4));
+                high_pass * low_pass
+            })
+            .collect();
+
+        // Similarity exponent from 32 (strict) down to 2 (lenient)
+        let mask_exponent = 2.0_f32.powf(5.0 - 4.0 * sensitivity.clamp(0.0, 1.0));
+
+        let mut planner = RealFftPlanner::<f32>::new();
+        Self {
+            fft_size,
+            hop,
+            window,
+            window_norm,
+            forward: planner.plan_fft_forward(fft_size),
+            inverse: planner.plan_fft_inverse(fft_size),
+            band_weight,
+            mask_exponent,
+        }
+    }
+
+    /// Split a stereo pair into the center vocal and the instrumental.
+    /// `vocal + instrumental` reconstructs each input channel.
+    pub fn separate(&self, left: &[f32], right: &[f32]) -> Result<SeparatedStems, Box<dyn std::error::Error>> {
+        let n = self.fft_size;
+        let len = left.len().min(right.len());
+
+        // Pad a full frame on both sides so every sample is covered by the same number of frames
+        let pad = |channel: &[f32]| {
+            let mut padded = vec![0.0f32; n];
+            padded.extend_from_slice(&channel[..len]);
+            padded.resize(len + 2 * n, 0.0);
+            padded
+        };
+        let inputs = [pad(left), pad(right)];
+        let frame_count = (len + n) / self.hop + 1;
+
+        let mut vocal = vec![0.0f32; len + 3 * n];
+        let mut instrumental = [vec![0.0f32; len + 3 * n], vec![0.0f32; len + 3 * n]];
+
+        let bins = n / 2 + 1;
+        let mut time_buffer = vec![0.0f32; n];
+        let mut spectra = [vec![Complex32::new(0.0, 0.0); bins], vec![Complex32::new(0.0, 0.0); bins]];
+        let mut center = vec![Complex32::new(0.0, 0.0); bins];
+        let mut mask = vec![0.0f32; bins];
+
+        for frame in 0..frame_count {
+            let offset = frame * self.hop;
+            for (input, spectrum) in inputs.iter().zip(spectra.iter_mut()) {
+                for (i, sample) in time_buffer.iter_mut().enumerate() {
+                    *sample = input.get(offset + i).copied().unwrap_or(0.0) * self.window[i];
+                }
+                self.forward.process(&mut time_buffer, spectrum)?;
+            }
+
+            for k in 0..bins {
+                let (l, r) = (spectra[0][k], spectra[1][k]);
+                let power = l.norm_sqr() + r.norm_sqr();
+
+                // 1 when both sides carry the same level and phase, 0 for hard-panned or out-of-phase content
+                let similarity = if power > 1e-12 {
+                    (2.0 * (l * r.conj()).re / power).max(0.0)
+                } else {
+                    0.0
+                };
+                let target = similarity.powf(self.mask_exponent) * self.band_weight[k];
+                mask[k] = MASK_SMOOTHING * mask[k] + (1.0 - MASK_SMOOTHING) * target;
+
+                center[k] = (l + r) * (0.5 * mask[k]);
+                spectra[0][k] = l - center[k];
+                spectra[1][k] = r - center[k];
+            }
+
+            self.overlap_add(&mut center, &mut time_buffer, &mut vocal[offset..offset + n])?;
+            for (spectrum, output) in spectra.iter_mut().zip(instrumental.iter_mut()) {
+                self.overlap_add(spectrum, &mut time_buffer, &mut output[offset..offset + n])?;
+            }
+        }
+
+        let trim = |mut channel: Vec<f32>| {
+            channel.drain(..n);
+            channel.truncate(len);
+            channel
+        };
+        let [instrumental_left, instrumental_right] = instrumental;
+
+        Ok(SeparatedStems {
+            vocal: trim(vocal),
+            instrumental: [trim(instrumental_left), trim(instrumental_right)],
+        })
+    }
+
+    /// Inverse transform one masked frame and add it into `output` under the synthesis window
+    fn overlap_add(
+        &self,
+        spectrum: &mut [Complex32],
+        time_buffer: &mut [f32],
+        output: &mut [f32],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // The inverse real FFT expects purely real DC and Nyquist bins
+        spectrum[0].im = 0.0;
+        if let Some(last) = spectrum.last_mut() {
+            last.im = 0.0;
+        }
+        self.inverse.process(spectrum, time_buffer)?;
+
+        let scale = 1.0 / (self.fft_size as f32 * self.window_norm);
+        for ((out, &sample), &w) in output.iter_mut().zip(time_buffer.iter()).zip(&self.window) {
+            *out += sample * w * scale;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use crate::audio_types::{AudioMarker, MarkerKind};
+
+/// A raw RIFF chunk: four-character id plus payload (without the size header)
+pub struct RiffChunk {
+    pub id: [u8; 4],
+    pub data: Vec<u8>,
+}
+
+impl RiffChunk {
+    pub fn new(id: &[u8; 4], data: Vec<u8>) -> Self {
+        Self { id: *id, data }
+    }
+}
+
+/// Read every top-level chunk of a RIFF/WAVE file except the sample data
+pub fn read_chunks(file_path: &str) -> Result<Vec<RiffChunk>, Box<dyn std::error::Error>> {
+    let mut file = File::open(file_path)?;
+    let mut header = [0u8; 12];
+    file.read_exact(&mut header)?;
+    if &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+        return Err("Not a RIFF/WAVE file".into());
+    }
+
+    let file_len = file.metadata()?.len();
+    let mut chunks = Vec::new();
+    let mut offset = 12u64;
+
+    while offset + 8 <= file_len {
+        let mut chunk_header = [0u8; 8];
+        file.read_exact(&mut chunk_header)?;
+        let id = [chunk_header[0], chunk_header[1], chunk_header[2], chunk_header[3]];
+        let size = u32::from_le_bytes([chunk_header[4], chunk_header[5], chunk_header[6], chunk_header[7]]) as u64;
+        let padded_size = size + (size & 1);
+
+        if offset + 8 + size > file_len {
+            break; // Truncated chunk, stop scanning
+        }
+
+        if &id == b"data" {
+            file.seek(SeekFrom::Current(padded_size as i64))?;
+        } else {
+            let mut data = vec![0u8; size as usize];
+            file.read_exact(&mut data)?;
+            if size & 1 == 1 {
+                file.seek(SeekFrom::Current(1))?;
+            }
+            chunks.push(RiffChunk { id, data });
+        }
+
+        offset += 8 + padded_size;
+    }
+
+    Ok(chunks)
+}
+
+/// Append chunks to the end of a finalized WAV file and fix up the RIFF size
+pub fn append_chunks(file_path: &str, chunks: &[RiffChunk]) -> Result<(), Box<dyn std::error::Error>> {
+    if chunks.is_empty() {
+        return Ok(());
+    }
+
+    let mut file = OpenOptions::new().read(true).write(true).open(file_path)?;
+    let mut end = file.seek(SeekFrom::End(0))?;
+
+    // Chunks start on even offsets
+    if end & 1 == 1 {
+        file.write_all(&[0])?;
+        end += 1;
+    }
+
+    for chunk in chunks {
+        file.write_all(&chunk.id)?;
+        file.write_all(&(chunk.data.len() as u32).to_le_bytes())?;
+        file.write_all(&chunk.data)?;
+        end += 8 + chunk.data.len() as u64;
+        if chunk.data.len() & 1 == 1 {
+            file.write_all(&[0])?;
+            end += 1;
+        }
+    }
+
+    if end - 8 > u32::MAX as u64 {
+        return Err("WAV file too large for RIFF metadata".into());
+    }
+
+    file.seek(SeekFrom::Start(4))?;
+    file.write_all(&((end - 8) as u32).to_le_bytes())?;
+    file.flush()?;
+    Ok(())
+}
+
+/// Encode markers as `cue `, `LIST/adtl` and (for loops) `smpl` chunks
+pub fn encode_markers(markers: &[AudioMarker], sample_rate: u32) -> Vec<RiffChunk> {
+    if markers.is_empty() {
+        return Vec::new();
+    }
+
+    // Cue points
+    let mut cue = Vec::with_capacity(4 + markers.len() * 24);
+    cue.extend_from_slice(&(markers.len() as u32).to_le_bytes());
+    for marker in markers {
+        cue.extend_from_slice(&marker.id.to_le_bytes());
+        cue.extend_from_slice(&marker.position.to_le_bytes()); // Play order position
+        cue.extend_from_slice(b"data");
+        cue.extend_from_slice(&0u32.to_le_bytes()); // Chunk start
+        cue.extend_from_slice(&0u32.to_le_bytes()); // Block start
+        cue.extend_from_slice(&marker.position.to_le_bytes());
+    }
+
+    // Associated data list: a label for every cue point, a labeled text for every span
+    let mut adtl = Vec::new();
+    adtl.extend_from_slice(b"adtl");
+    for marker in markers {
+        let mut labl = marker.id.to_le_bytes().to_vec();
+        labl.extend_from_slice(marker.label.as_bytes());
+        labl.push(0);
+        push_sub_chunk(&mut adtl, b"labl", &labl);
+
+        if let Some(length) = marker.length.filter(|_| marker.kind != MarkerKind::Marker) {
+            let mut ltxt = marker.id.to_le_bytes().to_vec();
+            ltxt.extend_from_slice(&length.to_le_bytes());
+            ltxt.extend_from_slice(b"rgn ");
+            ltxt.extend_from_slice(&[0u8; 8]); // Country, language, dialect, code page
+            push_sub_chunk(&mut adtl, b"ltxt", &ltxt);
+        }
+    }
+
+    let mut chunks = vec![RiffChunk::new(b"cue ", cue), RiffChunk::new(b"LIST", adtl)];
+
+    // Sampler loops
+    let loops: Vec<&AudioMarker> = markers
+        .iter()
+        .filter(|m| m.kind == MarkerKind::Loop && m.length.unwrap_or(0) > 0)
+        .collect();
+
+    if !loops.is_empty() {
+        let sample_period = (1_000_000_000.0 / sample_rate.max(1) as f64).round() as u32;
+        let mut smpl = Vec::with_capacity(36 + loops.len() * 24);
+        smpl.extend_from_slice(&0u32.to_le_bytes()); // Manufacturer
+        smpl.extend_from_slice(&0u32.to_le_bytes()); // Product
+        smpl.extend_from_slice(&sample_period.to_le_bytes());
+        smpl.extend_from_slice(&60u32.to_le_bytes()); // MIDI unity note (middle C)
+        smpl.extend_from_slice(&0u32.to_le_bytes()); // MIDI pitch fraction
+        smpl.extend_from_slice(&0u32.to_le_bytes()); // SMPTE format
+        smpl.extend_from_slice(&0u32.to_le_bytes()); // SMPTE offset
+        smpl.extend_from_slice(&(loops.len() as u32).to_le_bytes());
+        smpl.extend_from_slice(&0u32.to_le_bytes()); // Sampler data
+
+        for marker in loops {
+            let length = marker.length.unwrap_or(1);
+            smpl.extend_from_slice(&marker.id.to_le_bytes());
+            smpl.extend_from_slice(&0u32.to_le_bytes()); // Forward loop
+            smpl.extend_from_slice(&marker.position.to_le_bytes());
+            smpl.extend_from_slice(&marker.position.saturating_add(length - 1).to_le_bytes()); // Inclusive end
+            smpl.extend_from_slice(&0u32.to_le_bytes()); // Fraction
+            smpl.extend_from_slice(&0u32.to_le_bytes()); // Loop forever
+        }
+
+        chunks.push(RiffChunk::new(b"smpl", smpl));
+    }
+
+    chunks
+}
+
+/// Rebuild the marker list from `cue `, `LIST/adtl` and `smpl` chunks
+pub fn decode_markers(chunks: &[RiffChunk]) -> Vec<AudioMarker> {
+    let mut markers: BTreeMap<u32, AudioMarker> = BTreeMap::new();
+
+    for chunk in chunks.iter().filter(|c| &c.id == b"cue ") {
+        let count = read_u32(&chunk.data, 0).unwrap_or(0) as usize;
+        for i in 0..count {
+            let base = 4 + i * 24;
+            let (Some(id), Some(position)) = (read_u32(&chunk.data, base), read_u32(&chunk.data, base + 20)) else {
+                break;
+            };
+            markers.insert(id, AudioMarker {
+                id,
+                kind: MarkerKind::Marker,
+                label: String::new(),
+                position,
+                length: None,
+            });
+        }
+    }
+
+    for chunk in chunks.iter().filter(|c| &c.id == b"LIST" && c.data.starts_with(b"adtl")) {
+        let mut offset = 4;
+        while offset + 8 <= chunk.data.len() {
+            let id = &chunk.data[offset..offset + 4];
+            let size = read_u32(&chunk.data, offset + 4).unwrap_or(0) as usize;
+            let start = offset + 8;
+            let end = (start + size).min(chunk.data.len());
+            let body = &chunk.data[start..end];
+
+            if let Some(marker) = read_u32(body, 0).and_then(|cue_id| markers.get_mut(&cue_id)) {
+                match id {
+                    b"labl" => marker.label = read_text(&body[4..]),
+                    b"ltxt" => {
+                        if let Some(length) = read_u32(body, 4) {
+                            marker.kind = MarkerKind::Region;
+                            marker.length = Some(length);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            offset = start + size + (size & 1);
+        }
+    }
+
+    for chunk in chunks.iter().filter(|c| &c.id == b"smpl") {
+        let count = read_u32(&chunk.data, 28).unwrap_or(0) as usize;
+        for i in 0..count {
+            let base = 36 + i * 24;
+            let (Some(id), Some(start), Some(end)) = (
+                read_u32(&chunk.data, base),
+                read_u32(&chunk.data, base + 8),
+                read_u32(&chunk.data, base + 12),
+            ) else {
+                break;
+            };
+
+            let marker = markers.entry(id).or_insert_with(|| AudioMarker {
+                id,
+                kind: MarkerKind::Loop,
+                label: String::new(),
+                position: start,
+                length: None,
+            });
+            marker.kind = MarkerKind::Loop;
+            marker.position = start;
+            marker.length = Some(end.saturating_sub(start) + 1);
+        }
+    }
+
+    let mut markers: Vec<AudioMarker> = markers.into_values().collect();
+    markers.sort_by_key(|m| (m.position, m.id));
+    markers
+}
+
+fn push_sub_chunk(buffer: &mut Vec<u8>, id: &[u8; 4], data: &[u8]) {
+    buffer.extend_from_slice(id);
+    buffer.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(data);
+    if data.len() & 1 == 1 {
+        buffer.push(0);
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn read_text(data: &[u8]) -> String {
+    let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..end]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_types::AudioBuffer;
+
+    fn test_markers() -> Vec<AudioMarker> {
+        vec![
+            AudioMarker { id: 1, kind: MarkerKind::Marker, label: "Drop".into(), position: 100, length: None },
+            AudioMarker { id: 2, kind: MarkerKind::Region, label: "Verse".into(), position: 2_000, length: Some(4_410) },
+            AudioMarker { id: 3, kind: MarkerKind::Loop, label: "Hook".into(), position: 8_000, length: Some(22_050) },
+        ]
+    }
+
+    fn assert_same(read: &[AudioMarker], written: &[AudioMarker]) {
+        assert_eq!(serde_json::to_value(read).unwrap(), serde_json::to_value(written).unwrap());
+    }
+
+    #[test]
+    fn marker_chunks_round_trip() {
+        let markers = test_markers();
+        let chunks = encode_markers(&markers, 44100);
+        assert_same(&decode_markers(&chunks), &markers);
+    }
+
+    #[test]
+    fn wav_markers_round_trip() {
+        let markers = test_markers();
+        let audio = AudioBuffer {
+            channels: vec![vec![0.0; 44100]; 2],
+            sample_rate: 44100,
+            duration: 1.0,
+        };
+
+        let path = std::env::temp_dir().join("ez-audio-markers.wav");
+        let path = path.to_str().unwrap();
+        crate::audio_loader::AudioLoader::save_as_wav(&audio, path, 24, &markers, None).unwrap();
+        let loaded = crate::audio_loader::AudioLoader::open(path).unwrap();
+        std::fs::remove_file(path).ok();
+
+        assert_eq!(loaded.buffer.channels[0].len(), 44100);
+        assert_same(&loaded.markers, &markers);
+    }
+}